@@ -2,7 +2,8 @@
 
 use winit::{
 	application::ApplicationHandler,
-	event::{Event, WindowEvent, StartCause},
+	event::{Event, WindowEvent, StartCause, KeyEvent, ElementState},
+	keyboard::{Key, NamedKey},
 	event_loop::{ActiveEventLoop, EventLoop, ControlFlow},
 	window::{Window, WindowId, /*WindowLevel*/},
 };
@@ -15,6 +16,7 @@ use common::*;
 pub mod ui;
 
 pub mod renderer;
+pub mod software_renderer;
 pub mod painter;
 pub mod view;
 pub mod util;
@@ -89,6 +91,10 @@ struct ApplicationHost {
 	view: view::View,
 
 	app_window: Option<ApplicationWindow>,
+
+	/// Whether to save the next frame to a png once it has been presented.
+	screenshot_requested: bool,
+	screenshot_renderer: Option<renderer::OffscreenRenderer>,
 }
 
 impl ApplicationHost {
@@ -109,18 +115,41 @@ impl ApplicationHost {
 			view,
 
 			app_window: None,
+			screenshot_requested: false,
+			screenshot_renderer: None,
 		})
 	}
 
 	fn redraw(&mut self) {
-		let Some(ApplicationWindow{window, renderer}) = self.app_window.as_mut() else {
+		let Some(ApplicationWindow{window, renderer, ime_allowed}) = self.app_window.as_mut() else {
 			return
 		};
 
+		self.view.prepare_resources(&self.gfx_core, &mut self.shared_resources);
+
 		self.painter.clear();
 		self.ui_system.run(&mut self.painter, |ui| {
 			self.view.build(ui);
 		});
+
+		window.set_cursor(self.ui_system.input.cursor_icon);
+
+		// Only allow the IME while a text field has focus, otherwise it eats key presses
+		let wants_ime = self.ui_system.input.wants_text_input();
+		if wants_ime != *ime_allowed {
+			window.set_ime_allowed(wants_ime);
+			*ime_allowed = wants_ime;
+		}
+
+		if let Some(cursor_area) = self.ui_system.input.ime_cursor_area.get() {
+			use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+			let view_to_physical = self.ui_system.viewport.view_to_physical();
+			let min = view_to_physical * cursor_area.min;
+			let max = view_to_physical * cursor_area.max;
+
+			window.set_ime_cursor_area(PhysicalPosition::new(min.x, min.y), PhysicalSize::new(max.x - min.x, max.y - min.y));
+		}
 		
 		renderer.prepare(&self.gfx_core, &self.shared_resources, &self.painter,
 			&self.ui_system.viewport, &mut *self.ui_system.text_atlas.borrow_mut());
@@ -128,8 +157,67 @@ impl ApplicationHost {
 		window.pre_present_notify();
 		renderer.present(&self.gfx_core, &self.shared_resources);
 
+		let format = renderer.format();
+
+		if std::mem::take(&mut self.screenshot_requested) {
+			self.export_screenshot(format);
+		}
+
 		self.ui_system.prepare_next_frame();
 	}
+
+	/// Render the current frame again offscreen and save it next to the working directory.
+	/// Uses the same format as the window where possible, so that the result matches what was presented.
+	fn export_screenshot(&mut self, format: wgpu::TextureFormat) {
+		use std::time::{SystemTime, UNIX_EPOCH};
+
+		let viewport = self.ui_system.viewport;
+		let size = viewport.size.to_vec2i();
+		let format = renderer::OffscreenRenderer::closest_format(format);
+
+		// The window may have been recreated with a different format since the last screenshot
+		if self.screenshot_renderer.as_ref().is_some_and(|renderer| renderer.format() != format) {
+			self.screenshot_renderer = None;
+		}
+
+		let renderer = self.screenshot_renderer.get_or_insert_with(|| {
+			renderer::OffscreenRenderer::new(&self.gfx_core, &self.shared_resources, size, format)
+		});
+
+		renderer.resize(&self.gfx_core, size);
+		renderer.prepare(&self.gfx_core, &self.shared_resources, &self.painter, &viewport, self.ui_system.text_atlas.get_mut());
+
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+		let path = format!("screenshot-{timestamp}.png");
+
+		let result = renderer.render(&self.gfx_core, &self.shared_resources)
+			.and_then(|image| image.save(&path).map_err(Into::into));
+
+		match result {
+			Ok(()) => log::info!("Saved screenshot to '{path}'"),
+			Err(err) => log::error!("Failed to export screenshot: {err}"),
+		}
+	}
+
+	fn toggle_input_recording(&mut self) {
+		use std::time::{SystemTime, UNIX_EPOCH};
+
+		let input = &mut self.ui_system.input;
+
+		let Some(recording) = input.stop_recording() else {
+			input.start_recording();
+			log::info!("Started recording input");
+			return
+		};
+
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+		let path = format!("input-recording-{timestamp}.jsonl");
+
+		match recording.save(&path) {
+			Ok(()) => log::info!("Saved {} frames of input to '{path}'", recording.num_frames()),
+			Err(err) => log::error!("Failed to save input recording: {err}"),
+		}
+	}
 }
 
 
@@ -150,9 +238,12 @@ impl ApplicationHandler for ApplicationHost {
 			Vec2i::new(physical_size.width, physical_size.height)
 		});
 
+		self.ui_system.set_window_resize_border(WINDOW_RESIZE_BORDER);
+
 		self.app_window = Some(ApplicationWindow {
 			window: window.clone(),
 			renderer,
+			ime_allowed: false,
 		});
 
 		self.redraw();
@@ -160,7 +251,7 @@ impl ApplicationHandler for ApplicationHost {
 		window.set_visible(true);
 	}
 
-	fn window_event(&mut self, event_loop: &ActiveEventLoop, _window_id: WindowId, event: WindowEvent) {
+	fn window_event(&mut self, _event_loop: &ActiveEventLoop, _window_id: WindowId, event: WindowEvent) {
 		match event {
 			WindowEvent::RedrawRequested => {
 				self.redraw();
@@ -169,7 +260,7 @@ impl ApplicationHandler for ApplicationHost {
 			WindowEvent::Resized(new_physical_size) => {
 				use winit::dpi::PhysicalSize;
 
-				let Some(ApplicationWindow{window, renderer}) = self.app_window.as_mut() else {
+				let Some(ApplicationWindow{window, renderer, ..}) = self.app_window.as_mut() else {
 					return
 				};
 
@@ -179,14 +270,44 @@ impl ApplicationHandler for ApplicationHost {
 				let Vec2i{x, y} = self.ui_system.min_size;
 				let new_min_size = PhysicalSize::new(x as u32, y as u32);
 				window.set_min_inner_size(Some(new_min_size));
+
+				// Maximized windows can't be resized by their edges
+				let resize_border = if window.is_maximized() { 0.0 } else { WINDOW_RESIZE_BORDER };
+				self.ui_system.set_window_resize_border(resize_border);
 			}
 
 			// TODO(pat.m): theme change
 			// TODO(pat.m): dpi change
 
 			WindowEvent::CloseRequested => {
-				// TODO(pat.m): this should defer to the view
-				event_loop.exit();
+				self.view.request_close();
+
+				if let Some(ApplicationWindow{window, ..}) = self.app_window.as_ref() {
+					window.request_redraw();
+				}
+			}
+
+			// F9 starts and stops recording input, e.g., for attaching to bug reports
+			WindowEvent::KeyboardInput{ event: KeyEvent{ logical_key: Key::Named(NamedKey::F9), state: ElementState::Pressed, repeat: false, .. }, .. } => {
+				self.toggle_input_recording();
+			}
+
+			// F12 toggles the widget inspector
+			WindowEvent::KeyboardInput{ event: KeyEvent{ logical_key: Key::Named(NamedKey::F12), state: ElementState::Pressed, repeat: false, .. }, .. } => {
+				self.ui_system.toggle_inspector();
+
+				if let Some(ApplicationWindow{window, ..}) = self.app_window.as_ref() {
+					window.request_redraw();
+				}
+			}
+
+			// F10 exports a screenshot of the next frame
+			WindowEvent::KeyboardInput{ event: KeyEvent{ logical_key: Key::Named(NamedKey::F10), state: ElementState::Pressed, repeat: false, .. }, .. } => {
+				self.screenshot_requested = true;
+
+				if let Some(ApplicationWindow{window, ..}) = self.app_window.as_ref() {
+					window.request_redraw();
+				}
 			}
 
 			WindowEvent::MouseInput{..}
@@ -194,7 +315,16 @@ impl ApplicationHandler for ApplicationHost {
 				| WindowEvent::CursorLeft{..}
 				| WindowEvent::CursorMoved{..}
 				| WindowEvent::MouseWheel{..}
+				| WindowEvent::PinchGesture{..}
+				| WindowEvent::PanGesture{..}
+				| WindowEvent::RotationGesture{..}
 				| WindowEvent::KeyboardInput{..}
+				| WindowEvent::ModifiersChanged{..}
+				| WindowEvent::Focused{..}
+				| WindowEvent::HoveredFile{..}
+				| WindowEvent::HoveredFileCancelled
+				| WindowEvent::DroppedFile{..}
+				| WindowEvent::Ime{..}
 			=> {
 				let Some(ApplicationWindow{window, ..}) = self.app_window.as_mut() else {
 					return
@@ -213,6 +343,10 @@ impl ApplicationHandler for ApplicationHost {
 						}
 					}
 
+					ui::SendEventResponse::ToggleMaximizeWindow => {
+						window.set_maximized(!window.is_maximized());
+					}
+
 					_ => {}
 				}
 			}
@@ -232,10 +366,24 @@ impl ApplicationHandler for ApplicationHost {
 			return
 		};
 
+		if std::mem::take(&mut self.view.wants_minimize) {
+			window.set_minimized(true);
+		}
+
+		if std::mem::take(&mut self.view.wants_toggle_maximize) {
+			window.set_maximized(!window.is_maximized());
+		}
+
 		if self.ui_system.should_redraw() {
 			window.request_redraw();
 		}
 
+		// Sleep until the next animation frame or timer, if any
+		match self.ui_system.next_redraw_deadline() {
+			Some(deadline) => event_loop.set_control_flow(ControlFlow::WaitUntil(deadline)),
+			None => event_loop.set_control_flow(ControlFlow::Wait),
+		}
+
 		// If the minsize of the window has changed make sure we update it
 		let Vec2i{x, y} = self.ui_system.min_size;
 		let new_min_size = PhysicalSize::new(x as u32, y as u32);
@@ -251,8 +399,15 @@ impl ApplicationHandler for ApplicationHost {
 }
 
 
+// Since the window is undecorated we have to provide our own resize borders.
+const WINDOW_RESIZE_BORDER: f32 = 6.0;
+
+
 struct ApplicationWindow {
 	window: Arc<Window>,
 	renderer: renderer::Renderer,
+
+	/// Whether we've last told the window to allow IME input.
+	ime_allowed: bool,
 }
 
@@ -8,6 +8,8 @@ use lyon::tessellation::*;
 
 pub use lyon::path::builder::BorderRadii;
 
+use std::ops::Range;
+
 
 pub struct Painter {
 	pub geometry: VertexBuffers<renderer::Vertex, u32>,
@@ -23,6 +25,16 @@ pub struct Painter {
 	pub color: Color,
 	pub uv_rect: Option<Aabb2>,
 	pub clip_rect: Option<Aabb2>,
+
+	// Texture to sample for each run of indices, where None refers to the text atlas.
+	batch_starts: Vec<(Option<renderer::TextureHandle>, u32)>,
+}
+
+/// A range of indices that should be drawn sampling from a particular texture.
+#[derive(Debug, Clone)]
+pub struct DrawBatch {
+	pub texture: Option<renderer::TextureHandle>,
+	pub index_range: Range<u32>,
 }
 
 impl Painter {
@@ -40,6 +52,8 @@ impl Painter {
 			color: Color::white(),
 			uv_rect: None,
 			clip_rect: None,
+
+			batch_starts: Vec::new(),
 		}
 	}
 
@@ -52,6 +66,50 @@ impl Painter {
 		self.color = Color::white();
 		self.uv_rect = None;
 		self.clip_rect = None;
+
+		self.batch_starts.clear();
+	}
+
+	/// Split geometry into runs of indices that sample from the same texture.
+	pub fn draw_batches(&self) -> impl Iterator<Item=DrawBatch> + '_ {
+		let num_indices = self.geometry.indices.len() as u32;
+
+		// If no texture has been set yet, then everything samples from the text atlas.
+		let first_batch = match self.batch_starts.first() {
+			Some((_, 0)) => None,
+			_ => Some((None, 0)),
+		};
+
+		let batch_starts = first_batch.into_iter().chain(self.batch_starts.iter().copied());
+		let batch_ends = batch_starts.clone().skip(1).map(|(_, start)| start).chain(std::iter::once(num_indices));
+
+		batch_starts.zip(batch_ends)
+			.filter(|((_, start), end)| start < end)
+			.map(|((texture, start), end)| DrawBatch {
+				texture,
+				index_range: start..end,
+			})
+	}
+
+	fn current_texture(&self) -> Option<renderer::TextureHandle> {
+		self.batch_starts.last().and_then(|&(texture, _)| texture)
+	}
+
+	fn set_texture(&mut self, texture: Option<renderer::TextureHandle>) {
+		if self.current_texture() == texture {
+			return
+		}
+
+		let start = self.geometry.indices.len() as u32;
+
+		// Replace the last batch if nothing has been drawn with it
+		if let Some(last) = self.batch_starts.last_mut()
+			&& last.1 == start
+		{
+			last.0 = texture;
+		} else {
+			self.batch_starts.push((texture, start));
+		}
 	}
 
 	fn with_stroker(&mut self, f: impl FnOnce(&mut StrokeTessellator, &StrokeOptions, &mut BuffersBuilder<'_, renderer::Vertex, u32, VertexConstructor>))
@@ -170,6 +228,29 @@ impl Painter {
 		});
 	}
 
+	/// Fills a rect with colors interpolated between each corner.
+	/// Colors are given clockwise starting from the top left corner.
+	pub fn gradient_rect(&mut self, rect: impl Into<Aabb2>, colors: [Color; 4]) {
+		let Aabb2{min, max} = rect.into();
+		let clip_rect = to_4u16(self.clip_rect);
+
+		let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+
+		let base_index = self.geometry.vertices.len() as u32;
+
+		for (pos, color) in corners.into_iter().zip(colors) {
+			self.geometry.vertices.push(renderer::Vertex {
+				pos: pos.into(),
+				color: color.into(),
+				uv: [1.0, 1.0],
+				clip_rect,
+			});
+		}
+
+		// top left, bottom left, top right - then top right, bottom left, bottom right
+		self.geometry.indices.extend([0, 3, 1, 1, 3, 2].map(|i| base_index + i));
+	}
+
 	// TODO(pat.m): have this callback take a custom type so we can adapt types
 	pub fn stroke_path(&mut self, make_path: impl FnOnce(&mut PathBufferBuilder)) {
 		let mut scratch_path = std::mem::take(&mut self.scratch_path);
@@ -207,6 +288,20 @@ impl Painter {
 		self.scratch_path = scratch_path;
 	}
 
+	/// Draws `rect` sampling the region `uv_rect` of `texture`, where uvs are normalised to [0, 1].
+	pub fn image(&mut self, rect: impl Into<Aabb2>, texture: renderer::TextureHandle, uv_rect: impl Into<Option<Aabb2>>) {
+		let uv_rect = uv_rect.into().unwrap_or(Aabb2::new(Vec2::zero(), Vec2::one()));
+		let prev_uv_rect = self.uv_rect;
+
+		self.set_texture(Some(texture));
+		self.set_uv_rect(uv_rect);
+		self.rect(rect);
+
+		// Everything else samples from the text atlas
+		self.set_uv_rect(prev_uv_rect);
+		self.set_texture(None);
+	}
+
 	pub fn line(&mut self, start: impl Into<Vec2>, end: impl Into<Vec2>) {
 		use lyon::path::PathEvent;
 
@@ -230,6 +325,48 @@ impl Painter {
 
 		self.with_stroker(move |tess, opts, builder| tess.tessellate(events, opts, builder).unwrap());
 	}
+
+	/// Strokes a circular arc starting at `start_angle` and sweeping clockwise by `sweep_angle` radians.
+	pub fn arc_outline(&mut self, center: impl Into<Vec2>, r: f32, start_angle: f32, sweep_angle: f32) {
+		let center = center.into();
+
+		// Use as few segments as possible while staying within tolerance of the true arc
+		let tolerance = self.stroke_options.tolerance.min(r);
+		let segment_angle = 2.0 * (1.0 - tolerance / r).acos();
+		let num_segments = ((sweep_angle.abs() / segment_angle).ceil() as usize).clamp(4, 128);
+
+		let point_at = |t: f32| {
+			let angle = start_angle + sweep_angle * t;
+			to_point(center + Vec2::new(angle.cos(), angle.sin()) * r)
+		};
+
+		self.stroke_path(|builder| {
+			builder.begin(point_at(0.0), &[]);
+
+			for segment in 1..=num_segments {
+				builder.line_to(point_at(segment as f32 / num_segments as f32), &[]);
+			}
+
+			builder.end(false);
+		});
+	}
+
+	/// Strokes an open path through each of `points` in turn.
+	pub fn polyline(&mut self, points: &[Vec2]) {
+		let [first, rest @ ..] = points else {
+			return
+		};
+
+		self.stroke_path(|builder| {
+			builder.begin(to_point(*first), &[]);
+
+			for &point in rest {
+				builder.line_to(to_point(point), &[]);
+			}
+
+			builder.end(false);
+		});
+	}
 }
 
 
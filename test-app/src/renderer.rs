@@ -7,16 +7,8 @@ pub struct Renderer {
 	surface: wgpu::Surface<'static>,
 	surface_config: wgpu::SurfaceConfiguration,
 
-	framebuffer: wgpu::TextureView,
-
-	// TODO(pat.m): not shared bc of msaa_samples
-	render_pipeline: wgpu::RenderPipeline,
-
-
-	vertex_bytes: u64,
-	index_bytes: u64,
-
-	msaa_samples: u32,
+	target: RenderTarget,
+	frame: PreparedFrame,
 }
 
 impl Renderer {
@@ -39,10 +31,277 @@ impl Renderer {
 		let swapchain_capabilities = surface.get_capabilities(&core.adapter);
 		let swapchain_format = swapchain_capabilities.formats[0];
 
-		let supported_sample_counts = core.adapter.get_texture_format_features(swapchain_format).flags.supported_sample_counts();
+		let target_size = Vec2i::new(surface_config.width as i32, surface_config.height as i32);
+		let target = RenderTarget::new(core, shared_resources, swapchain_format, target_size);
+
+		log::trace!("renderer init completed");
+
+		Ok(Renderer {
+			surface,
+			surface_config,
+
+			target,
+			frame: PreparedFrame::default(),
+		})
+	}
+
+	/// The format of the window's swapchain.
+	pub fn format(&self) -> wgpu::TextureFormat {
+		self.surface_config.format
+	}
+
+	#[instrument(name = "Renderer::resize", skip_all)]
+	pub fn resize(&mut self, core: &GraphicsCore, new_width: u32, new_height: u32) {
+		self.surface_config.width = new_width.max(1);
+		self.surface_config.height = new_height.max(1);
+
+		if new_width > 0 && new_height > 0 {
+			self.surface.configure(&core.device, &self.surface_config);
+			self.target.resize(core, Vec2i::new(new_width as i32, new_height as i32));
+		}
+	}
+
+	#[instrument(name = "Renderer::prepare", skip_all)]
+	pub fn prepare(&mut self, core: &GraphicsCore, shared_resources: &SharedResources,
+		painter: &Painter, viewport: &ui::Viewport, text_atlas: &mut ui::TextAtlas)
+	{
+		self.frame.prepare(core, shared_resources, painter, viewport, text_atlas);
+	}
+
+	#[instrument(name = "Renderer::present", skip_all)]
+	pub fn present(&mut self, core: &GraphicsCore, shared_resources: &SharedResources) {
+		if self.surface_config.width <= 0 || self.surface_config.height <= 0 {
+			return;
+		}
+
+		let current_frame_surface_texture = match self.surface.get_current_texture() {
+			Ok(frame) => frame,
+			Err(_) => {
+				self.resize(core, self.surface_config.width, self.surface_config.height);
+				self.surface.get_current_texture()
+					.expect("Failed to acquire next frame texture")
+			}
+		};
+
+		let current_frame_view = current_frame_surface_texture.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+		let mut encoder = core.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+		self.target.draw(&mut encoder, shared_resources, &self.frame, &current_frame_view);
+
+		core.queue.submit(Some(encoder.finish()));
+		current_frame_surface_texture.present();
+	}
+}
+
+
+
+/// Used for formats that can't be read back as 8 bit RGBA or BGRA.
+const FALLBACK_OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Renders into a texture of any size instead of a window, and reads the result back to the cpu.
+/// For screenshots, and for rendering with no window at all - e.g., on software adapters like lavapipe.
+pub struct OffscreenRenderer {
+	texture: wgpu::Texture,
+	texture_view: wgpu::TextureView,
+	readback_buffer: wgpu::Buffer,
+
+	target: RenderTarget,
+	frame: PreparedFrame,
+}
+
+impl OffscreenRenderer {
+	/// Renders to `format` so that results match a window using the same format, e.g., whether blending
+	/// happens in linear or sRGB space. See [`OffscreenRenderer::closest_format`].
+	#[instrument(skip_all)]
+	pub fn new(core: &GraphicsCore, shared_resources: &SharedResources, size: Vec2i, format: wgpu::TextureFormat) -> OffscreenRenderer {
+		let format = Self::closest_format(format);
+		let size = Vec2i::new(size.x.max(1), size.y.max(1));
+		let (texture, texture_view, readback_buffer) = Self::create_texture(&core.device, size, format);
+
+		OffscreenRenderer {
+			texture,
+			texture_view,
+			readback_buffer,
+
+			target: RenderTarget::new(core, shared_resources, format, size),
+			frame: PreparedFrame::default(),
+		}
+	}
+
+	fn create_texture(device: &wgpu::Device, size: Vec2i, format: wgpu::TextureFormat) -> (wgpu::Texture, wgpu::TextureView, wgpu::Buffer) {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some("Offscreen target"),
+			size: wgpu::Extent3d {
+				width: size.x as u32,
+				height: size.y as u32,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format,
+			usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+			view_formats: &[],
+		});
+
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+		let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Offscreen readback"),
+			size: padded_bytes_per_row(size.x as u32) as u64 * size.y as u64,
+			usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+			mapped_at_creation: false,
+		});
+
+		(texture, texture_view, readback_buffer)
+	}
+
+	/// The format that will actually be rendered to when `format` is requested.
+	/// Formats other than 8 bit RGBA or BGRA can't be read back directly, so fall back to `Rgba8UnormSrgb`.
+	pub fn closest_format(format: wgpu::TextureFormat) -> wgpu::TextureFormat {
+		use wgpu::TextureFormat::*;
+
+		match format {
+			Rgba8UnormSrgb | Rgba8Unorm | Bgra8UnormSrgb | Bgra8Unorm => format,
+			_ => FALLBACK_OFFSCREEN_FORMAT,
+		}
+	}
+
+	pub fn size(&self) -> Vec2i {
+		self.target.size
+	}
+
+	pub fn format(&self) -> wgpu::TextureFormat {
+		self.target.format
+	}
+
+	#[instrument(name = "OffscreenRenderer::resize", skip_all)]
+	pub fn resize(&mut self, core: &GraphicsCore, new_size: Vec2i) {
+		let new_size = Vec2i::new(new_size.x.max(1), new_size.y.max(1));
+		if new_size == self.target.size {
+			return
+		}
+
+		self.texture.destroy();
+		(self.texture, self.texture_view, self.readback_buffer) = Self::create_texture(&core.device, new_size, self.target.format);
+		self.target.resize(core, new_size);
+	}
+
+	#[instrument(name = "OffscreenRenderer::prepare", skip_all)]
+	pub fn prepare(&mut self, core: &GraphicsCore, shared_resources: &SharedResources,
+		painter: &Painter, viewport: &ui::Viewport, text_atlas: &mut ui::TextAtlas)
+	{
+		self.frame.prepare(core, shared_resources, painter, viewport, text_atlas);
+	}
+
+	/// Draw whatever was last prepared and wait for the result.
+	/// The returned image is sRGB encoded and not premultiplied, so it can be written straight to a png.
+	#[instrument(name = "OffscreenRenderer::render", skip_all)]
+	pub fn render(&mut self, core: &GraphicsCore, shared_resources: &SharedResources) -> anyhow::Result<image::RgbaImage> {
+		let Vec2i{x: width, y: height} = self.target.size;
+		let bytes_per_row = padded_bytes_per_row(width as u32);
+
+		let format = self.target.format;
+		let is_bgra = matches!(format, wgpu::TextureFormat::Bgra8UnormSrgb | wgpu::TextureFormat::Bgra8Unorm);
+
+		let mut encoder = core.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+		self.target.draw(&mut encoder, shared_resources, &self.frame, &self.texture_view);
+
+		encoder.copy_texture_to_buffer(
+			self.texture.as_image_copy(),
+			wgpu::ImageCopyBuffer {
+				buffer: &self.readback_buffer,
+				layout: wgpu::ImageDataLayout {
+					offset: 0,
+					bytes_per_row: Some(bytes_per_row),
+					rows_per_image: None,
+				},
+			},
+			self.texture.size(),
+		);
+
+		core.queue.submit(Some(encoder.finish()));
+
+		let buffer_slice = self.readback_buffer.slice(..);
+
+		let (sender, receiver) = std::sync::mpsc::channel();
+		buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+			let _ = sender.send(result);
+		});
+
+		core.device.poll(wgpu::Maintain::Wait);
+		receiver.recv()??;
+
+		let mut image = image::RgbaImage::new(width as u32, height as u32);
+
+		{
+			let mapped = buffer_slice.get_mapped_range();
+			let rows = mapped.chunks_exact(bytes_per_row as usize);
+
+			for (row, image_row) in rows.zip(image.rows_mut()) {
+				for (texel, pixel) in row.chunks_exact(4).zip(image_row) {
+					let texel = match is_bgra {
+						true => [texel[2], texel[1], texel[0], texel[3]],
+						false => [texel[0], texel[1], texel[2], texel[3]],
+					};
+
+					pixel.0 = unpremultiply(texel, format.is_srgb());
+				}
+			}
+		}
+
+		self.readback_buffer.unmap();
+
+		Ok(image)
+	}
+}
+
+/// Rows copied out of textures must be aligned to `COPY_BYTES_PER_ROW_ALIGNMENT`.
+fn padded_bytes_per_row(width: u32) -> u32 {
+	let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+	(width * 4).div_ceil(alignment) * alignment
+}
+
+/// Blending into sRGB targets happens in linear space, so unpremultiplying their texels has to as well.
+fn unpremultiply([r, g, b, a]: [u8; 4], is_srgb: bool) -> [u8; 4] {
+	use crate::software_renderer::{srgb_u8_to_linear, linear_to_srgb_u8};
+
+	if a == 0 {
+		return [0; 4]
+	}
+
+	let alpha = a as f32 / 255.0;
+
+	let unpremultiply_channel = |value: u8| match is_srgb {
+		true => linear_to_srgb_u8(srgb_u8_to_linear(value) / alpha),
+		false => (value as f32 / alpha).round().min(255.0) as u8,
+	};
+
+	[unpremultiply_channel(r), unpremultiply_channel(g), unpremultiply_channel(b), a]
+}
+
+
+
+/// A pipeline and multisampled framebuffer for drawing to textures of a given format and size.
+/// Shared between the window and offscreen renderers.
+struct RenderTarget {
+	format: wgpu::TextureFormat,
+	size: Vec2i,
+
+	framebuffer: wgpu::TextureView,
+
+	// TODO(pat.m): not shared bc of msaa_samples
+	render_pipeline: wgpu::RenderPipeline,
+
+	msaa_samples: u32,
+}
+
+impl RenderTarget {
+	fn new(core: &GraphicsCore, shared_resources: &SharedResources, format: wgpu::TextureFormat, size: Vec2i) -> RenderTarget {
+		let supported_sample_counts = core.adapter.get_texture_format_features(format).flags.supported_sample_counts();
 		let msaa_samples = supported_sample_counts.into_iter().max().unwrap_or(1);
 
-		log::info!("Using MSAA x{msaa_samples}");
+		log::info!("Using MSAA x{msaa_samples} for {format:?}");
 
 		// Premultiplied dual-source blending
 		let blend_state = wgpu::BlendState {
@@ -79,7 +338,7 @@ impl Renderer {
 				module: &shared_resources.shader_module,
 				entry_point: "fs_main",
 				targets: &[Some(wgpu::ColorTargetState {
-					format: swapchain_format,
+					format,
 					write_mask: wgpu::ColorWrites::all(),
 					blend: Some(blend_state),
 				})],
@@ -101,28 +360,23 @@ impl Renderer {
 			multiview: None,
 		});
 
-		let framebuffer = Self::create_framebuffer(&core.device, &surface_config, msaa_samples);
+		let framebuffer = Self::create_framebuffer(&core.device, format, size, msaa_samples);
 
-		log::trace!("renderer init completed");
-
-		Ok(Renderer {
-			surface,
-			surface_config,
+		RenderTarget {
+			format,
+			size,
 
-			render_pipeline,
 			framebuffer,
-
-			vertex_bytes: 0,
-			index_bytes: 0,
+			render_pipeline,
 
 			msaa_samples,
-		})
+		}
 	}
 
-	fn create_framebuffer(device: &wgpu::Device, surface_conf: &wgpu::SurfaceConfiguration, sample_count: u32) -> wgpu::TextureView {
+	fn create_framebuffer(device: &wgpu::Device, format: wgpu::TextureFormat, size: Vec2i, sample_count: u32) -> wgpu::TextureView {
 		let multisampled_texture_extent = wgpu::Extent3d {
-			width: surface_conf.width,
-			height: surface_conf.height,
+			width: size.x as u32,
+			height: size.y as u32,
 			depth_or_array_layers: 1,
 		};
 
@@ -132,7 +386,7 @@ impl Renderer {
 			mip_level_count: 1,
 			sample_count,
 			dimension: wgpu::TextureDimension::D2,
-			format: surface_conf.format,
+			format,
 			usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
 			view_formats: &[],
 		};
@@ -142,19 +396,67 @@ impl Renderer {
 			.create_view(&wgpu::TextureViewDescriptor::default())
 	}
 
-	#[instrument(name = "Renderer::resize", skip_all)]
-	pub fn resize(&mut self, core: &GraphicsCore, new_width: u32, new_height: u32) {
-		self.surface_config.width = new_width.max(1);
-		self.surface_config.height = new_height.max(1);
+	fn resize(&mut self, core: &GraphicsCore, new_size: Vec2i) {
+		self.size = new_size;
+		self.framebuffer = Self::create_framebuffer(&core.device, self.format, new_size, self.msaa_samples);
+	}
 
-		if new_width > 0 && new_height > 0 {
-			self.surface.configure(&core.device, &self.surface_config);
-			self.framebuffer = Self::create_framebuffer(&core.device, &self.surface_config, self.msaa_samples);
+	/// Record a pass drawing `frame` into `output`, resolving from the multisampled framebuffer if needed.
+	fn draw(&self, encoder: &mut wgpu::CommandEncoder, shared_resources: &SharedResources, frame: &PreparedFrame, output: &wgpu::TextureView) {
+		let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: None,
+			color_attachments: &[Some(
+				if self.msaa_samples == 1 {
+					wgpu::RenderPassColorAttachment {
+						view: output,
+						resolve_target: None,
+						ops: wgpu::Operations {
+							load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+							store: wgpu::StoreOp::Store,
+						},
+					}
+				} else {
+					wgpu::RenderPassColorAttachment {
+						view: &self.framebuffer,
+						resolve_target: Some(output),
+						ops: wgpu::Operations {
+							load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+							store: wgpu::StoreOp::Discard,
+						},
+					}
+				}
+			)],
+			depth_stencil_attachment: None,
+			timestamp_writes: None,
+			occlusion_query_set: None,
+		});
+
+		if frame.index_bytes > 0 {
+			pass.set_pipeline(&self.render_pipeline);
+			pass.set_index_buffer(shared_resources.index_buffer.slice(0..frame.index_bytes), wgpu::IndexFormat::Uint32);
+			pass.set_vertex_buffer(0, shared_resources.vertex_buffer.slice(0..frame.vertex_bytes));
+
+			for batch in frame.draw_batches.iter() {
+				pass.set_bind_group(0, shared_resources.bind_group(batch.texture), &[]);
+				pass.draw_indexed(batch.index_range.clone(), 0, 0..1);
+			}
 		}
 	}
+}
 
-	#[instrument(name = "Renderer::prepare", skip_all)]
-	pub fn prepare(&mut self, core: &GraphicsCore, shared_resources: &SharedResources,
+
+
+/// Painter output uploaded to `SharedResources`, ready to be drawn.
+/// Geometry lives in the shared buffers, so only the most recently prepared frame can be drawn.
+#[derive(Default)]
+struct PreparedFrame {
+	vertex_bytes: u64,
+	index_bytes: u64,
+	draw_batches: Vec<painter::DrawBatch>,
+}
+
+impl PreparedFrame {
+	fn prepare(&mut self, core: &GraphicsCore, shared_resources: &SharedResources,
 		painter: &Painter, viewport: &ui::Viewport, text_atlas: &mut ui::TextAtlas)
 	{
 		let vertex_bytes = bytemuck::cast_slice(&painter.geometry.vertices);
@@ -165,6 +467,9 @@ impl Renderer {
 
 		self.vertex_bytes = vertex_bytes.len() as u64;
 		self.index_bytes = index_bytes.len() as u64;
+
+		self.draw_batches.clear();
+		self.draw_batches.extend(painter.draw_batches());
 		
 		let [basis_x, basis_y, translation] = viewport.view_to_clip().columns();
 
@@ -175,6 +480,7 @@ impl Renderer {
 			}
 		]));
 
+		// The atlas texture is shared, so any renderer can consume updates
 		for ui::GlyphUpdate{image, dst_pos} in text_atlas.glyph_updates.drain(..) {
 			let placement = image.placement;
 
@@ -213,69 +519,6 @@ impl Renderer {
 			core.queue.write_texture(image_copy, &data, data_layout, size);
 		}
 	}
-
-	#[instrument(name = "Renderer::present", skip_all)]
-	pub fn present(&mut self, core: &GraphicsCore, shared_resources: &SharedResources) {
-		if self.surface_config.width <= 0 || self.surface_config.height <= 0 {
-			return;
-		}
-
-		let current_frame_surface_texture = match self.surface.get_current_texture() {
-			Ok(frame) => frame,
-			Err(_) => {
-				self.resize(core, self.surface_config.width, self.surface_config.height);
-				self.surface.get_current_texture()
-					.expect("Failed to acquire next frame texture")
-			}
-		};
-
-		let current_frame_view = current_frame_surface_texture.texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-		let mut encoder = core.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
-
-		let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-			label: None,
-			color_attachments: &[Some(
-				if self.msaa_samples == 1 {
-					wgpu::RenderPassColorAttachment {
-						view: &current_frame_view,
-						resolve_target: None,
-						ops: wgpu::Operations {
-							load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-							store: wgpu::StoreOp::Store,
-						},
-					}
-				} else {
-					wgpu::RenderPassColorAttachment {
-						view: &self.framebuffer,
-						resolve_target: Some(&current_frame_view),
-						ops: wgpu::Operations {
-							load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-							store: wgpu::StoreOp::Discard,
-						},
-					}
-				}
-			)],
-			depth_stencil_attachment: None,
-			timestamp_writes: None,
-			occlusion_query_set: None,
-		});
-
-		if self.index_bytes > 0 {
-			pass.set_pipeline(&self.render_pipeline);
-			pass.set_bind_group(0, &shared_resources.bind_group, &[]);
-			pass.set_index_buffer(shared_resources.index_buffer.slice(0..self.index_bytes), wgpu::IndexFormat::Uint32);
-			pass.set_vertex_buffer(0, shared_resources.vertex_buffer.slice(0..self.vertex_bytes));
-
-			let num_elements = (self.index_bytes/4) as u32;
-			pass.draw_indexed(0..num_elements, 0, 0..1);
-		}
-
-		drop(pass);
-
-		core.queue.submit(Some(encoder.finish()));
-		current_frame_surface_texture.present();
-	}
 }
 
 
@@ -314,6 +557,47 @@ unsafe impl bytemuck::Zeroable for Vertex {}
 
 
 
+slotmap::new_key_type! {
+	pub struct TextureKey;
+}
+
+/// Refers to a texture registered with `SharedResources`.
+/// Carries the size of the texture so that the ui can size things appropriately without access to the renderer.
+#[derive(Debug, Copy, Clone)]
+pub struct TextureHandle {
+	key: TextureKey,
+	size: Vec2i,
+}
+
+impl TextureHandle {
+	pub(crate) fn new(key: TextureKey, size: Vec2i) -> TextureHandle {
+		TextureHandle { key, size }
+	}
+
+	pub(crate) fn key(&self) -> TextureKey {
+		self.key
+	}
+
+	pub fn size(&self) -> Vec2i {
+		self.size
+	}
+}
+
+impl PartialEq for TextureHandle {
+	fn eq(&self, other: &Self) -> bool {
+		self.key == other.key
+	}
+}
+
+impl Eq for TextureHandle {}
+
+struct RegisteredTexture {
+	texture: wgpu::Texture,
+	bind_group: wgpu::BindGroup,
+}
+
+
+
 
 
 pub struct SharedResources {
@@ -322,7 +606,11 @@ pub struct SharedResources {
 
 	globals_buffer: wgpu::Buffer,
 	bind_group: wgpu::BindGroup,
+	bind_group_layout: wgpu::BindGroupLayout,
 	pipeline_layout: wgpu::PipelineLayout,
+	sampler: wgpu::Sampler,
+
+	textures: SlotMap<TextureKey, RegisteredTexture>,
 
 	vertex_buffer: wgpu::Buffer,
 	index_buffer: wgpu::Buffer,
@@ -472,11 +760,94 @@ impl SharedResources {
 			text_atlas_texture,
 			globals_buffer,
 			bind_group,
+			bind_group_layout,
 			pipeline_layout,
+			sampler: atlas_sampler,
+			textures: SlotMap::with_key(),
 			vertex_buffer,
 			index_buffer,
 		})
 	}
+
+	/// Creates a texture from tightly packed, non-premultiplied sRGB RGBA8 data.
+	pub fn create_texture(&mut self, core: &GraphicsCore, size: Vec2i, rgba_data: &[u8]) -> TextureHandle {
+		let width = size.x.max(1) as u32;
+		let height = size.y.max(1) as u32;
+
+		assert!(rgba_data.len() >= (width * height * 4) as usize, "Not enough data for texture of size {width}x{height}");
+
+		let texture = core.device.create_texture(&wgpu::TextureDescriptor {
+			label: Some("Image"),
+			size: wgpu::Extent3d {
+				width,
+				height,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format: wgpu::TextureFormat::Rgba8UnormSrgb,
+			usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+			view_formats: &[wgpu::TextureFormat::Rgba8UnormSrgb]
+		});
+
+		let image_copy = wgpu::ImageCopyTexture {
+			texture: &texture,
+			origin: wgpu::Origin3d::ZERO,
+			mip_level: 0,
+			aspect: wgpu::TextureAspect::All,
+		};
+
+		let data_layout = wgpu::ImageDataLayout {
+			offset: 0,
+			bytes_per_row: Some(width * 4),
+			rows_per_image: None,
+		};
+
+		core.queue.write_texture(image_copy, rgba_data, data_layout, texture.size());
+
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+		let bind_group = core.device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Image bind group"),
+			layout: &self.bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry {
+					binding: 0,
+					resource: wgpu::BindingResource::Buffer(self.globals_buffer.as_entire_buffer_binding()),
+				},
+				wgpu::BindGroupEntry {
+					binding: 1,
+					resource: wgpu::BindingResource::TextureView(&texture_view),
+				},
+				wgpu::BindGroupEntry {
+					binding: 2,
+					resource: wgpu::BindingResource::Sampler(&self.sampler),
+				},
+			],
+		});
+
+		let key = self.textures.insert(RegisteredTexture {texture, bind_group});
+
+		TextureHandle {
+			key,
+			size: Vec2i::new(width as i32, height as i32),
+		}
+	}
+
+	pub fn destroy_texture(&mut self, handle: TextureHandle) {
+		if let Some(registered) = self.textures.remove(handle.key) {
+			registered.texture.destroy();
+		}
+	}
+
+	/// Get the bind group for a given texture, where None refers to the text atlas.
+	/// Falls back to the text atlas if the texture has been destroyed.
+	fn bind_group(&self, texture: Option<TextureHandle>) -> &wgpu::BindGroup {
+		texture.and_then(|handle| self.textures.get(handle.key))
+			.map(|registered| &registered.bind_group)
+			.unwrap_or(&self.bind_group)
+	}
 }
 
 
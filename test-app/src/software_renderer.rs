@@ -0,0 +1,435 @@
+use crate::prelude::*;
+use crate::renderer::{TextureHandle, TextureKey, Vertex};
+
+use image::RgbaImage;
+
+use std::sync::OnceLock;
+
+
+// Must match fs_main in shaders.wgsl
+const SUBPIXEL_SPREAD: f32 = 0.2 / 2048.0;
+const SUBPIXEL_DISTRIBUTION: f32 = 0.3;
+
+/// Where each sample lies within a pixel relative to its center - the standard 4x MSAA pattern.
+const MSAA_SAMPLE_OFFSETS: [(f32, f32); 4] = [
+	(-2.0 / 16.0, -6.0 / 16.0),
+	( 6.0 / 16.0, -2.0 / 16.0),
+	(-6.0 / 16.0,  2.0 / 16.0),
+	( 2.0 / 16.0,  6.0 / 16.0),
+];
+
+
+/// Rasterizes [`Painter`] geometry on the cpu with the same semantics as `shaders.wgsl` - clip rects, premultiplied
+/// dual-source blending, and sampling from the text atlas or image textures.
+/// For pixel-level tests without a gpu. It can't present to a window yet, so it isn't usable as a fallback for
+/// machines where wgpu can't get an adapter.
+pub struct SoftwareRenderer {
+	text_atlas: CpuTexture,
+	textures: SlotMap<TextureKey, CpuTexture>,
+
+	/// Whether to rasterize with 4x MSAA, like the gpu renderer does where supported.
+	pub msaa: bool,
+}
+
+impl SoftwareRenderer {
+	pub fn new() -> SoftwareRenderer {
+		let mut text_atlas = CpuTexture::new(ui::TEXT_ATLAS_SIZE, ui::TEXT_ATLAS_SIZE);
+
+		// Non-textured geometry samples from a white pixel in the bottom right corner
+		let last_row_start = text_atlas.data.len() - text_atlas.width as usize;
+		let last_row_end = last_row_start + text_atlas.width as usize;
+		text_atlas.data[last_row_end - 4 .. last_row_end].fill([255; 4]);
+
+		SoftwareRenderer {
+			text_atlas,
+			textures: SlotMap::with_key(),
+			msaa: true,
+		}
+	}
+
+	/// Creates a texture from tightly packed, non-premultiplied sRGB RGBA8 data.
+	pub fn create_texture(&mut self, size: Vec2i, rgba_data: &[u8]) -> TextureHandle {
+		let width = size.x.max(1) as u32;
+		let height = size.y.max(1) as u32;
+
+		assert!(rgba_data.len() >= (width * height * 4) as usize, "Not enough data for texture of size {width}x{height}");
+
+		let mut texture = CpuTexture::new(width, height);
+		for (texel, rgba) in texture.data.iter_mut().zip(rgba_data.chunks_exact(4)) {
+			texel.copy_from_slice(rgba);
+		}
+
+		let key = self.textures.insert(texture);
+		TextureHandle::new(key, Vec2i::new(width as i32, height as i32))
+	}
+
+	pub fn destroy_texture(&mut self, handle: TextureHandle) {
+		self.textures.remove(handle.key());
+	}
+
+	/// Copy newly rasterized glyphs into our copy of the text atlas.
+	/// Like `Renderer::prepare`, this consumes the atlas' pending glyph updates, so only one renderer should be
+	/// fed from a given atlas.
+	pub fn update_text_atlas(&mut self, text_atlas: &mut ui::TextAtlas) {
+		for ui::GlyphUpdate{image, dst_pos} in text_atlas.glyph_updates.drain(..) {
+			let width = image.placement.width as usize;
+			let height = image.placement.height as usize;
+
+			let is_mask = image.content == cosmic_text::SwashContent::Mask;
+
+			for y in 0..height {
+				for x in 0..width {
+					let texel = match is_mask {
+						true => [255, 255, 255, image.data[y * width + x]],
+						false => {
+							let index = (y * width + x) * 4;
+							[image.data[index], image.data[index + 1], image.data[index + 2], image.data[index + 3]]
+						}
+					};
+
+					self.text_atlas.set(dst_pos.x as u32 + x as u32, dst_pos.y as u32 + y as u32, texel);
+				}
+			}
+		}
+	}
+
+	/// Rasterize everything painted into an image the size of the viewport.
+	/// The result is sRGB encoded and not premultiplied, so it can be written straight to a png.
+	#[instrument(name = "SoftwareRenderer::render", skip_all)]
+	pub fn render(&self, painter: &Painter, viewport: &ui::Viewport) -> RgbaImage {
+		let width = viewport.size.x.max(1.0) as u32;
+		let height = viewport.size.y.max(1.0) as u32;
+
+		let sample_offsets: &[(f32, f32)] = match self.msaa {
+			true => &MSAA_SAMPLE_OFFSETS,
+			false => &[(0.0, 0.0)],
+		};
+
+		let mut target = RenderTarget {
+			width,
+			height,
+			sample_offsets,
+			samples: vec![[0.0; 4]; (width * height) as usize * sample_offsets.len()],
+		};
+
+		let view_to_clip = viewport.view_to_clip();
+		let vertices = &painter.geometry.vertices;
+		let indices = &painter.geometry.indices;
+
+		for batch in painter.draw_batches() {
+			// Same as the gpu renderer, missing textures fall back to the text atlas
+			let texture = batch.texture
+				.and_then(|handle| self.textures.get(handle.key()))
+				.unwrap_or(&self.text_atlas);
+
+			let batch_indices = &indices[batch.index_range.start as usize .. batch.index_range.end as usize];
+
+			for triangle in batch_indices.chunks_exact(3) {
+				let triangle = [0, 1, 2].map(|i| &vertices[triangle[i] as usize]);
+				target.draw_triangle(triangle, view_to_clip, texture);
+			}
+		}
+
+		target.resolve()
+	}
+}
+
+
+/// Returns how many pixels differ between two images by more than `tolerance` in any channel,
+/// or None if they aren't the same size.
+pub fn count_differing_pixels(a: &RgbaImage, b: &RgbaImage, tolerance: u8) -> Option<usize> {
+	if a.dimensions() != b.dimensions() {
+		return None
+	}
+
+	let count = a.pixels().zip(b.pixels())
+		.filter(|(pixel_a, pixel_b)| {
+			pixel_a.0.iter().zip(pixel_b.0.iter())
+				.any(|(&channel_a, &channel_b)| channel_a.abs_diff(channel_b) > tolerance)
+		})
+		.count();
+
+	Some(count)
+}
+
+
+
+/// Non-premultiplied sRGB RGBA8, like the gpu textures.
+struct CpuTexture {
+	width: u32,
+	height: u32,
+	data: Vec<[u8; 4]>,
+}
+
+impl CpuTexture {
+	fn new(width: u32, height: u32) -> CpuTexture {
+		CpuTexture {
+			width,
+			height,
+			data: vec![[0; 4]; (width * height) as usize],
+		}
+	}
+
+	fn set(&mut self, x: u32, y: u32, texel: [u8; 4]) {
+		if x < self.width && y < self.height {
+			self.data[(y * self.width + x) as usize] = texel;
+		}
+	}
+
+	/// Linear filtering with clamp to edge addressing. Returns linear color.
+	fn sample(&self, uv: Vec2) -> [f32; 4] {
+		let x = uv.x * self.width as f32 - 0.5;
+		let y = uv.y * self.height as f32 - 0.5;
+
+		let x0 = x.floor();
+		let y0 = y.floor();
+		let fx = x - x0;
+		let fy = y - y0;
+
+		let texel = |tx: f32, ty: f32| {
+			let tx = (tx as i32).clamp(0, self.width as i32 - 1) as u32;
+			let ty = (ty as i32).clamp(0, self.height as i32 - 1) as u32;
+			let [r, g, b, a] = self.data[(ty * self.width + tx) as usize];
+			[srgb_u8_to_linear(r), srgb_u8_to_linear(g), srgb_u8_to_linear(b), a as f32 / 255.0]
+		};
+
+		let top = lerp4(texel(x0, y0), texel(x0 + 1.0, y0), fx);
+		let bottom = lerp4(texel(x0, y0 + 1.0), texel(x0 + 1.0, y0 + 1.0), fx);
+		lerp4(top, bottom, fy)
+	}
+}
+
+
+struct RenderTarget<'s> {
+	width: u32,
+	height: u32,
+	sample_offsets: &'s [(f32, f32)],
+
+	/// Premultiplied linear color, `sample_offsets.len()` samples per pixel.
+	samples: Vec<[f32; 4]>,
+}
+
+impl RenderTarget<'_> {
+	fn draw_triangle(&mut self, vertices: [&Vertex; 3], view_to_clip: Mat2x3, texture: &CpuTexture) {
+		let clip_positions = vertices.map(|vertex| view_to_clip * Vec2::new(vertex.pos[0], vertex.pos[1]));
+
+		// Back faces are culled, with counter-clockwise front faces in clip space
+		let [a, b, c] = clip_positions;
+		if orient(a, b, c) <= 0.0 {
+			return
+		}
+
+		let width = self.width as f32;
+		let height = self.height as f32;
+		let [a, b, c] = clip_positions.map(|pos| Vec2::new((pos.x + 1.0) / 2.0 * width, (1.0 - pos.y) / 2.0 * height));
+
+		// Flipping y flips the winding, so swap to keep the inside of every edge positive
+		let ([a, b, c], vertices) = match orient(a, b, c) < 0.0 {
+			true => ([a, c, b], [vertices[0], vertices[2], vertices[1]]),
+			false => ([a, b, c], vertices),
+		};
+
+		let area = orient(a, b, c);
+
+		// The clip rect comes from the provoking vertex, since it isn't interpolated
+		let [clip_min_x, clip_max_x, clip_min_y, clip_max_y] = vertices[0].clip_rect.map(|v| v as f32);
+
+		let min_x = a.x.min(b.x).min(c.x).floor().max(0.0).max(clip_min_x) as u32;
+		let min_y = a.y.min(b.y).min(c.y).floor().max(0.0).max(clip_min_y) as u32;
+		let max_x = a.x.max(b.x).max(c.x).ceil().min(width).min(clip_max_x) as u32;
+		let max_y = a.y.max(b.y).max(c.y).ceil().min(height).min(clip_max_y) as u32;
+
+		let edges = [(b, c), (c, a), (a, b)];
+		let num_samples = self.sample_offsets.len();
+
+		for y in min_y..max_y {
+			for x in min_x..max_x {
+				let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+				if center.x < clip_min_x || center.x >= clip_max_x || center.y < clip_min_y || center.y >= clip_max_y {
+					continue
+				}
+
+				let pixel_index = (y * self.width + x) as usize * num_samples;
+
+				let mut coverage = [false; MSAA_SAMPLE_OFFSETS.len()];
+				for (covered, &(offset_x, offset_y)) in coverage.iter_mut().zip(self.sample_offsets) {
+					let sample_pos = center + Vec2::new(offset_x, offset_y);
+					*covered = edges.iter().all(|&(start, end)| is_inside_edge(start, end, sample_pos));
+				}
+
+				if !coverage.iter().any(|&covered| covered) {
+					continue
+				}
+
+				// Like the gpu, shade once per pixel at its center
+				let weights = edges.map(|(start, end)| orient(start, end, center) / area);
+				let (color, blend_factors) = shade(vertices, weights, texture);
+
+				for (sample_index, _) in coverage.iter().enumerate().filter(|(_, &covered)| covered) {
+					let dst = &mut self.samples[pixel_index + sample_index];
+
+					// One * src + (1 - src1) * dst for color, One * src + (1 - src.a) * dst for alpha
+					for channel in 0..3 {
+						dst[channel] = color[channel] + dst[channel] * (1.0 - blend_factors[channel]);
+					}
+
+					dst[3] = color[3] + dst[3] * (1.0 - color[3]);
+				}
+			}
+		}
+	}
+
+	/// Average the samples of each pixel and convert to non-premultiplied sRGB.
+	fn resolve(&self) -> RgbaImage {
+		let num_samples = self.sample_offsets.len();
+
+		RgbaImage::from_fn(self.width, self.height, |x, y| {
+			let pixel_index = (y * self.width + x) as usize * num_samples;
+
+			let mut sum = [0.0; 4];
+			for sample in &self.samples[pixel_index .. pixel_index + num_samples] {
+				for channel in 0..4 {
+					sum[channel] += sample[channel];
+				}
+			}
+
+			let [r, g, b, a] = sum.map(|v| v / num_samples as f32);
+			if a <= 0.0 {
+				return image::Rgba([0; 4])
+			}
+
+			image::Rgba([
+				linear_to_srgb_u8(r / a),
+				linear_to_srgb_u8(g / a),
+				linear_to_srgb_u8(b / a),
+				(a.clamp(0.0, 1.0) * 255.0).round() as u8,
+			])
+		})
+	}
+}
+
+
+/// Equivalent of fs_main. Returns the premultiplied color and per-channel coverage used as the second blend source.
+fn shade(vertices: [&Vertex; 3], weights: [f32; 3], texture: &CpuTexture) -> ([f32; 4], [f32; 3]) {
+	let mut vertex_color = [0.0; 4];
+	let mut uv = Vec2::zero();
+
+	for (vertex, weight) in vertices.iter().zip(weights) {
+		for channel in 0..4 {
+			vertex_color[channel] += vertex.color[channel] * weight;
+		}
+
+		uv = uv + Vec2::new(vertex.uv[0], vertex.uv[1]) * weight;
+	}
+
+	let tex_color = texture.sample(uv);
+
+	let alpha_r1 = texture.sample(uv - Vec2::new(SUBPIXEL_SPREAD, 0.0))[3] * SUBPIXEL_DISTRIBUTION;
+	let alpha_g0 = tex_color[3] * (1.0 - SUBPIXEL_DISTRIBUTION * 2.0);
+	let alpha_b1 = texture.sample(uv + Vec2::new(SUBPIXEL_SPREAD, 0.0))[3] * SUBPIXEL_DISTRIBUTION;
+
+	let alpha_alpha = vertex_color[3] * (alpha_r1 + alpha_g0 + alpha_b1);
+
+	let alpha = [
+		alpha_r1 * 2.0 + alpha_g0,
+		alpha_r1 + alpha_g0 + alpha_b1,
+		alpha_g0 + alpha_b1 * 2.0,
+	].map(|v| v * vertex_color[3]);
+
+	let color = [
+		vertex_color[0] * tex_color[0] * alpha[0],
+		vertex_color[1] * tex_color[1] * alpha[1],
+		vertex_color[2] * tex_color[2] * alpha[2],
+		alpha_alpha,
+	];
+
+	(color, alpha)
+}
+
+
+fn orient(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+	(b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Whether `p` is inside the edge from `start` to `end`, using the top-left rule for points exactly on the edge
+/// so that pixels on edges shared between triangles are only drawn once.
+fn is_inside_edge(start: Vec2, end: Vec2, p: Vec2) -> bool {
+	let value = orient(start, end, p);
+	if value != 0.0 {
+		return value > 0.0
+	}
+
+	let delta = end - start;
+	(delta.y == 0.0 && delta.x > 0.0) || delta.y < 0.0
+}
+
+fn lerp4(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+	[0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+pub(crate) fn srgb_u8_to_linear(value: u8) -> f32 {
+	static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+
+	let table = TABLE.get_or_init(|| std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.0)));
+	table[value as usize]
+}
+
+pub(crate) fn linear_to_srgb_u8(value: f32) -> u8 {
+	(linear_to_srgb(value.clamp(0.0, 1.0)) * 255.0).round() as u8
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn render(size: f32, paint: impl FnOnce(&mut Painter)) -> RgbaImage {
+		let mut painter = Painter::new();
+		paint(&mut painter);
+
+		let viewport = ui::Viewport { size: Vec2::splat(size) };
+		SoftwareRenderer::new().render(&painter, &viewport)
+	}
+
+	#[test]
+	fn filled_rect() {
+		let image = render(16.0, |painter| {
+			painter.set_color([1.0, 0.0, 0.0, 1.0]);
+			painter.rect(Aabb2::new(Vec2::splat(4.0), Vec2::splat(12.0)));
+		});
+
+		assert_eq!(image.get_pixel(8, 8).0, [255, 0, 0, 255]);
+		assert_eq!(image.get_pixel(4, 4).0, [255, 0, 0, 255]);
+		assert_eq!(image.get_pixel(1, 1).0, [0, 0, 0, 0]);
+		assert_eq!(image.get_pixel(12, 12).0, [0, 0, 0, 0]);
+	}
+
+	#[test]
+	fn clip_rect_discards_outside() {
+		let image = render(16.0, |painter| {
+			painter.set_clip_rect(Aabb2::new(Vec2::zero(), Vec2::splat(8.0)));
+			painter.set_color([0.0, 0.0, 1.0, 1.0]);
+			painter.rect(Aabb2::new(Vec2::zero(), Vec2::splat(16.0)));
+		});
+
+		assert_eq!(image.get_pixel(4, 4).0, [0, 0, 255, 255]);
+		assert_eq!(image.get_pixel(10, 4).0, [0, 0, 0, 0]);
+		assert_eq!(image.get_pixel(4, 10).0, [0, 0, 0, 0]);
+	}
+
+	#[test]
+	fn translucent_blending() {
+		let image = render(4.0, |painter| {
+			painter.set_color([1.0, 1.0, 1.0, 1.0]);
+			painter.rect(Aabb2::new(Vec2::zero(), Vec2::splat(4.0)));
+
+			painter.set_color([0.0, 0.0, 0.0, 0.5]);
+			painter.rect(Aabb2::new(Vec2::zero(), Vec2::splat(4.0)));
+		});
+
+		// Half of linear white, in sRGB
+		assert_eq!(image.get_pixel(2, 2).0, [188, 188, 188, 255]);
+	}
+}
@@ -8,10 +8,19 @@ pub mod state_box;
 pub mod system;
 pub mod text;
 pub mod layout;
+pub mod layout_snapshot;
 pub mod hierarchy;
 pub mod input;
 pub mod viewport;
 pub mod widget_config;
+pub mod notifications;
+pub mod animation;
+pub mod drag_drop;
+pub mod inspector;
+pub mod recording;
+
+#[cfg(test)]
+pub mod harness;
 
 pub use widget::*;
 pub use widgets::*;
@@ -25,9 +34,15 @@ pub use layout::*;
 pub use hierarchy::*;
 pub use viewport::*;
 pub use input::*;
+pub use notifications::*;
+pub use animation::*;
+pub use drag_drop::*;
+pub use inspector::*;
+pub use recording::*;
 
 use std::any::TypeId;
 use std::marker::PhantomData;
+use std::time::Instant;
 
 
 
@@ -46,14 +61,26 @@ pub struct Ui<'ps> {
 	stack: RefCell<Vec<WidgetId>>,
 	widget_constraints: &'ps RefCell<LayoutConstraintMap>,
 	should_redraw: &'ps Cell<bool>,
+	redraw_deadline: &'ps Cell<Option<Instant>>,
 
 	persistent_state: &'ps PersistentState,
+	notifications: &'ps RefCell<NotificationQueue>,
 	pub text_atlas: &'ps RefCell<TextAtlas>,
 	pub input: &'ps Input,
+	pub clock: &'ps FrameClock,
 }
 
 
 impl Ui<'_> {
+	pub fn trigger_redraw(&self) {
+		self.should_redraw.set(true);
+	}
+
+	/// Request a frame at some point in the future, even if no input arrives before then.
+	pub fn request_redraw_at(&self, deadline: Instant) {
+		schedule_redraw(self.redraw_deadline, deadline);
+	}
+
 	pub fn parent_id(&self) -> Option<WidgetId> {
 		self.stack.borrow().last().copied()
 	}
@@ -96,8 +123,10 @@ impl Ui<'_> {
 					state: &mut widget_box.state,
 					text_atlas,
 					input: self.input,
+					clock: self.clock,
 					widget_id,
 					should_redraw: self.should_redraw,
+					redraw_deadline: self.redraw_deadline,
 				});
 
 				widgets.insert(widget_id, widget_box);
@@ -122,8 +151,10 @@ impl Ui<'_> {
 					state: &mut widget_box.state,
 					text_atlas,
 					input: self.input,
+					clock: self.clock,
 					widget_id,
 					should_redraw: self.should_redraw,
+					redraw_deadline: self.redraw_deadline,
 				});
 			}
 		}
@@ -0,0 +1,326 @@
+use crate::prelude::*;
+
+use std::time::{Duration, Instant};
+
+// https://m3.material.io/styles/motion/easing-and-duration/tokens-specs
+
+
+/// Animations shouldn't jump just because the app has been idle for a while.
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(50);
+
+pub const SHORT_DURATION: Duration = Duration::from_millis(150);
+pub const MEDIUM_DURATION: Duration = Duration::from_millis(300);
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct FrameClock {
+	/// The time the current frame started.
+	pub now: Instant,
+
+	/// Time since the last frame, clamped to something reasonable for animation.
+	pub delta: Duration,
+}
+
+impl FrameClock {
+	pub fn new() -> Self {
+		FrameClock {
+			now: Instant::now(),
+			delta: Duration::ZERO,
+		}
+	}
+
+	pub fn advance(&mut self, now: Instant) {
+		self.delta = now.saturating_duration_since(self.now).min(MAX_FRAME_DELTA);
+		self.now = now;
+	}
+
+	pub fn delta_secs(&self) -> f32 {
+		self.delta.as_secs_f32()
+	}
+}
+
+impl Default for FrameClock {
+	fn default() -> Self {
+		FrameClock::new()
+	}
+}
+
+
+/// Set `deadline` as the next time a redraw should happen, unless one is already scheduled sooner.
+pub fn schedule_redraw(redraw_deadline: &Cell<Option<Instant>>, deadline: Instant) {
+	let next_deadline = match redraw_deadline.get() {
+		Some(existing) => existing.min(deadline),
+		None => deadline,
+	};
+
+	redraw_deadline.set(Some(next_deadline));
+}
+
+
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Easing {
+	Linear,
+	EaseInCubic,
+	#[default]
+	EaseOutCubic,
+	EaseInOutCubic,
+}
+
+impl Easing {
+	pub fn apply(&self, t: f32) -> f32 {
+		let t = t.clamp(0.0, 1.0);
+
+		match self {
+			Easing::Linear => t,
+			Easing::EaseInCubic => t * t * t,
+			Easing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+			Easing::EaseInOutCubic => if t < 0.5 {
+				4.0 * t * t * t
+			} else {
+				1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+			},
+		}
+	}
+}
+
+
+
+/// Animates a value towards a target over a fixed duration.
+/// The first target set is snapped to, so that widgets don't animate in from some arbitrary value when created.
+#[derive(Debug, Clone, Copy)]
+pub struct Tween {
+	pub duration: Duration,
+	pub easing: Easing,
+
+	from: f32,
+	to: f32,
+	start_time: Option<Instant>,
+	initialised: bool,
+}
+
+impl Tween {
+	pub fn new(duration: Duration, easing: Easing) -> Self {
+		Tween {
+			duration,
+			easing,
+
+			from: 0.0,
+			to: 0.0,
+			start_time: None,
+			initialised: false,
+		}
+	}
+
+	pub fn set_target(&mut self, target: f32, now: Instant) {
+		if !self.initialised {
+			self.snap_to(target);
+			return
+		}
+
+		if self.to == target {
+			return
+		}
+
+		self.from = self.value(now);
+		self.to = target;
+		self.start_time = Some(now);
+	}
+
+	pub fn snap_to(&mut self, target: f32) {
+		self.from = target;
+		self.to = target;
+		self.start_time = None;
+		self.initialised = true;
+	}
+
+	pub fn target(&self) -> f32 {
+		self.to
+	}
+
+	pub fn value(&self, now: Instant) -> f32 {
+		let Some(start_time) = self.start_time else {
+			return self.to
+		};
+
+		let duration = self.duration.as_secs_f32();
+		if duration <= 0.0 {
+			return self.to
+		}
+
+		let t = now.saturating_duration_since(start_time).as_secs_f32() / duration;
+		self.easing.apply(t).lerp(self.from, self.to)
+	}
+
+	pub fn is_animating(&self, now: Instant) -> bool {
+		self.start_time.is_some_and(|start_time| now < start_time + self.duration)
+	}
+}
+
+impl Default for Tween {
+	fn default() -> Self {
+		Tween::new(SHORT_DURATION, Easing::default())
+	}
+}
+
+
+
+/// A damped spring, for animations that should respond naturally to targets changing mid-flight.
+/// Like [`Tween`], the first target set is snapped to.
+#[derive(Debug, Clone, Copy)]
+pub struct SpringAnimation {
+	pub stiffness: f32,
+	pub damping: f32,
+
+	value: f32,
+	velocity: f32,
+	target: f32,
+	initialised: bool,
+}
+
+impl SpringAnimation {
+	pub fn new(stiffness: f32, damping: f32) -> Self {
+		SpringAnimation {
+			stiffness,
+			damping,
+
+			value: 0.0,
+			velocity: 0.0,
+			target: 0.0,
+			initialised: false,
+		}
+	}
+
+	pub fn critically_damped(stiffness: f32) -> Self {
+		SpringAnimation::new(stiffness, 2.0 * stiffness.sqrt())
+	}
+
+	pub fn set_target(&mut self, target: f32) {
+		if !self.initialised {
+			self.snap_to(target);
+		}
+
+		self.target = target;
+	}
+
+	pub fn snap_to(&mut self, target: f32) {
+		self.value = target;
+		self.target = target;
+		self.velocity = 0.0;
+		self.initialised = true;
+	}
+
+	pub fn value(&self) -> f32 {
+		self.value
+	}
+
+	pub fn update(&mut self, delta: Duration) -> f32 {
+		// Fixed substeps keep stiff springs stable at low frame rates
+		let max_step = 1.0 / 240.0;
+		let mut remaining = delta.as_secs_f32();
+
+		while remaining > 0.0 {
+			let step = remaining.min(max_step);
+			remaining -= step;
+
+			let acceleration = self.stiffness * (self.target - self.value) - self.damping * self.velocity;
+			self.velocity += acceleration * step;
+			self.value += self.velocity * step;
+		}
+
+		if self.is_settled() {
+			self.value = self.target;
+			self.velocity = 0.0;
+		}
+
+		self.value
+	}
+
+	pub fn is_settled(&self) -> bool {
+		(self.target - self.value).abs() < 0.001 && self.velocity.abs() < 0.001
+	}
+}
+
+impl Default for SpringAnimation {
+	fn default() -> Self {
+		SpringAnimation::critically_damped(400.0)
+	}
+}
+
+
+
+/// Opacity of the overlay used to convey hover and press states.
+// https://m3.material.io/foundations/interaction/states/state-layers
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StateLayer {
+	opacity: Tween,
+}
+
+impl StateLayer {
+	pub fn update(&mut self, now: Instant, is_hovered: bool, is_pressed: bool) -> f32 {
+		let target = match (is_hovered, is_pressed) {
+			(_, true) => 0.32,
+			(true, false) => 0.16,
+			(false, false) => 0.0,
+		};
+
+		self.opacity.set_target(target, now);
+		self.opacity.value(now)
+	}
+
+	pub fn is_animating(&self, now: Instant) -> bool {
+		self.opacity.is_animating(now)
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const FRAME: Duration = Duration::from_millis(16);
+
+	#[test]
+	fn tween_reaches_target_after_duration() {
+		let start = Instant::now();
+		let mut tween = Tween::new(Duration::from_millis(100), Easing::Linear);
+
+		// The first target is snapped to
+		tween.set_target(0.0, start);
+		assert!(!tween.is_animating(start));
+
+		tween.set_target(1.0, start);
+		assert_eq!(tween.value(start), 0.0);
+		assert!(tween.is_animating(start));
+
+		let halfway = tween.value(start + Duration::from_millis(50));
+		assert!((halfway - 0.5).abs() < 0.01, "{halfway}");
+
+		let end = start + Duration::from_millis(100);
+		assert_eq!(tween.value(end), 1.0);
+		assert!(!tween.is_animating(end));
+	}
+
+	#[test]
+	fn spring_settles_on_target() {
+		let mut spring = SpringAnimation::default();
+
+		spring.set_target(0.0);
+		assert!(spring.is_settled());
+
+		spring.set_target(1.0);
+		assert!(!spring.is_settled());
+
+		let mut frames = 0;
+		while !spring.is_settled() {
+			let value = spring.update(FRAME);
+			assert!(value <= 1.0 + 0.001, "Critically damped spring overshot to {value}");
+
+			frames += 1;
+			assert!(frames < 120, "Spring didn't settle within two seconds");
+		}
+
+		assert_eq!(spring.value(), 1.0);
+	}
+}
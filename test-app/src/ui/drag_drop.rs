@@ -0,0 +1,301 @@
+use crate::ui::*;
+
+use std::any::Any;
+use std::path::PathBuf;
+
+
+/// How far the cursor must move from where a source was pressed before a drag starts.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+
+#[derive(Debug, Default)]
+pub struct DragDropState {
+	drag: Option<ActiveDrag>,
+
+	/// Set if a drag is cancelled while the mouse is still down, so that it doesn't immediately start again.
+	suppressed: bool,
+
+	/// The drop target under the cursor this frame, and whether it accepts the payload being dragged.
+	hovered_target: Option<(WidgetId, bool)>,
+
+	/// Whether a target has claimed the files being dragged over or dropped onto the window this frame.
+	files_claimed: bool,
+}
+
+#[derive(Debug)]
+struct ActiveDrag {
+	source: WidgetId,
+	payload: Box<dyn Any>,
+
+	/// Where the source was grabbed, relative to its top left corner.
+	grab_offset: Vec2,
+	source_size: Vec2,
+}
+
+impl DragDropState {
+	pub fn is_dragging(&self) -> bool {
+		self.drag.is_some()
+	}
+
+	pub fn is_dragging_payload<P: Any>(&self) -> bool {
+		self.drag.as_ref().is_some_and(|drag| drag.payload.is::<P>())
+	}
+
+	pub fn cancel(&mut self) {
+		if self.drag.take().is_some() {
+			self.suppressed = true;
+		}
+	}
+
+	pub(super) fn hovered_target(&self) -> Option<(WidgetId, bool)> {
+		self.hovered_target
+	}
+
+	pub(super) fn new_frame(&mut self) {
+		self.hovered_target = None;
+		self.files_claimed = false;
+	}
+}
+
+
+
+/// Root level layer that previews a drag at the cursor.
+#[derive(Debug)]
+pub struct DragPreview {}
+
+impl Widget for DragPreview {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Vertical);
+		ctx.constraints.padding.set_default(8.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		// Previews shouldn't affect the minimum size of the window
+		ctx.constraints.min_width.set(0.0);
+		ctx.constraints.min_height.set(0.0);
+
+		if ctx.style.fill.is_none() {
+			ctx.style.set_fill(WidgetColorRole::SurfaceContainerHighest);
+		}
+
+		if ctx.style.outline.is_none() {
+			ctx.style.set_outline(WidgetColorRole::Outline);
+		}
+
+		// Input needs to get through to drop targets beneath the preview
+		*ctx.input |= InputBehaviour::TRANSPARENT;
+	}
+}
+
+
+
+impl Ui<'_> {
+	pub fn is_dragging(&self) -> bool {
+		self.persistent_state.drag_drop.borrow().is_dragging()
+	}
+
+	pub fn is_dragging_payload<P: Any>(&self) -> bool {
+		self.persistent_state.drag_drop.borrow().is_dragging_payload::<P>()
+	}
+
+	pub fn cancel_drag(&self) {
+		self.persistent_state.drag_drop.borrow_mut().cancel();
+		self.trigger_redraw();
+	}
+
+	/// Cancel drags that were released without being accepted by a target, or dismissed with Escape.
+	pub(super) fn update_drag_drop(&self) {
+		let mut state = self.persistent_state.drag_drop.borrow_mut();
+
+		if !self.input.is_mouse_down(MouseButton::Left) {
+			state.suppressed = false;
+		}
+
+		if !self.input.dropped_files.is_empty() && !state.files_claimed {
+			log::warn!("{} file(s) were dropped onto the window, but nothing accepted them", self.input.dropped_files.len());
+		}
+
+		if !state.is_dragging() {
+			return
+		}
+
+		if self.input.was_key_pressed(NamedKey::Escape) {
+			state.cancel();
+			self.trigger_redraw();
+
+		} else if !self.input.is_mouse_down(MouseButton::Left) {
+			state.drag = None;
+			self.trigger_redraw();
+		}
+	}
+}
+
+
+
+impl<T> WidgetRef<'_, T> {
+	/// Allow dragging from this widget. `make_payload` is called once a drag starts, and the payload it returns
+	/// can be received by any [`WidgetRef::drop_target`] accepting that type.
+	pub fn drag_source<P: Any>(self, make_payload: impl FnOnce() -> P) -> Self {
+		self.drag_source_impl(make_payload, None::<fn()>)
+	}
+
+	/// Like [`WidgetRef::drag_source`], but `build_preview` adds the widgets to show at the cursor while dragging.
+	pub fn drag_source_with<P: Any>(self, make_payload: impl FnOnce() -> P, build_preview: impl FnOnce()) -> Self {
+		self.drag_source_impl(make_payload, Some(build_preview))
+	}
+
+	/// Returns whether a payload of type `P` is being dragged over this widget.
+	pub fn is_drag_over<P: Any>(&self) -> bool {
+		self.ui.is_dragging_payload::<P>() && self.is_drag_hovered()
+	}
+
+	/// Accept drops of payloads of type `P`, highlighting this widget while something is dragged over it.
+	/// Returns the payload on the frame it is dropped.
+	/// If targets are nested, whichever accepting target is checked first takes the drop.
+	pub fn drop_target<P: Any>(&self) -> Option<P> {
+		let ui = self.ui;
+
+		if !self.is_drag_hovered() {
+			return None
+		}
+
+		let mut state = ui.persistent_state.drag_drop.borrow_mut();
+		let accepts = state.is_dragging_payload::<P>();
+		state.hovered_target = Some((self.widget_id, accepts));
+
+		if !accepts || !ui.input.was_mouse_released(MouseButton::Left) {
+			return None
+		}
+
+		let drag = state.drag.take()?;
+		ui.trigger_redraw();
+
+		drag.payload.downcast::<P>().ok().map(|payload| *payload)
+	}
+
+	/// Returns whether files are being dragged over this widget from outside the app.
+	pub fn is_file_drag_over(&self) -> bool {
+		self.ui.input.is_file_hovered() && self.contains_file_drop_position()
+	}
+
+	/// Accept files dropped onto the window over this widget, highlighting it while files are dragged over it.
+	/// Returns the dropped paths, and where they were dropped, on the frame they are dropped.
+	/// Most platforms don't report the cursor position during an OS drag, in which case the first target to ask
+	/// takes the files, and they are dropped at the centre of its visible bounds.
+	pub fn file_drop_target(&self) -> Option<(Vec<PathBuf>, Vec2)> {
+		let ui = self.ui;
+		let input = ui.input;
+
+		let mut state = ui.persistent_state.drag_drop.borrow_mut();
+		let bounds = input.registered_widgets.get(&self.widget_id)?.bounds;
+
+		let drop_position = match input.file_drop_position {
+			Some(position) if self.contains_file_drop_position() => position,
+			None if !state.files_claimed => (bounds.min + bounds.max) / 2.0,
+			_ => return None,
+		};
+
+		if input.is_file_hovered() {
+			state.hovered_target = Some((self.widget_id, true));
+			state.files_claimed = true;
+			return None
+		}
+
+		if input.dropped_files.is_empty() {
+			return None
+		}
+
+		state.files_claimed = true;
+		ui.trigger_redraw();
+
+		Some((input.dropped_files.clone(), drop_position))
+	}
+
+	fn contains_file_drop_position(&self) -> bool {
+		let input = self.ui.input;
+
+		input.file_drop_position
+			.zip(input.registered_widgets.get(&self.widget_id))
+			.is_some_and(|(position, widget)| widget.contains_point(position))
+	}
+
+	fn is_drag_hovered(&self) -> bool {
+		let ui = self.ui;
+
+		let state = ui.persistent_state.drag_drop.borrow();
+		let Some(drag) = &state.drag else {
+			return false
+		};
+
+		if drag.source == self.widget_id {
+			return false
+		}
+
+		let hierarchy = ui.persistent_state.hierarchy.borrow();
+		ui.input.hovered_widget
+			.is_some_and(|hovered_id| hierarchy.is_descendant_of(hovered_id, self.widget_id))
+	}
+
+	fn drag_source_impl<P: Any>(self, make_payload: impl FnOnce() -> P, build_preview: Option<impl FnOnce()>) -> Self {
+		let ui = self.ui;
+		let input = ui.input;
+
+		let mut state = ui.persistent_state.drag_drop.borrow_mut();
+
+		if !state.is_dragging()
+			&& !state.suppressed
+			&& input.active_widget == Some(self.widget_id)
+			&& let Some(cursor_pos) = input.cursor_pos
+			&& let Some(delta) = input.mouse_drag_delta(MouseButton::Left)
+			&& delta.x.hypot(delta.y) > DRAG_THRESHOLD
+			&& let Some(source_bounds) = input.registered_widgets.get(&self.widget_id).map(|widget| widget.bounds)
+		{
+			let press_pos = cursor_pos - delta;
+
+			state.drag = Some(ActiveDrag {
+				source: self.widget_id,
+				payload: Box::new(make_payload()),
+				grab_offset: press_pos - source_bounds.min,
+				source_size: source_bounds.size(),
+			});
+
+			ui.trigger_redraw();
+		}
+
+		let Some(drag) = state.drag.as_ref().filter(|drag| drag.source == self.widget_id) else {
+			return self
+		};
+
+		let Some(cursor_pos) = input.cursor_pos else {
+			return self
+		};
+
+		let position = cursor_pos - drag.grab_offset;
+		let source_size = drag.source_size;
+
+		// Release so that the preview is free to query drag state
+		drop(state);
+
+		let preview = ui.add_widget_to(DragPreview{}, None);
+
+		let mut constraints = preview.constraints();
+		constraints.margin.left.set(position.x);
+		constraints.margin.top.set(position.y);
+
+		match build_preview {
+			Some(build_preview) => {
+				drop(constraints);
+				ui.with_parent(&preview, build_preview);
+			}
+
+			// Without a preview just show a box the size of the source
+			None => {
+				constraints.preferred_width.set(source_size.x);
+				constraints.preferred_height.set(source_size.y);
+			}
+		}
+
+		self
+	}
+}
@@ -0,0 +1,807 @@
+use crate::ui::*;
+use crate::software_renderer::SoftwareRenderer;
+use crate::renderer::TextureHandle;
+
+use winit::keyboard::SmolStr;
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+
+const FRAME_DURATION: Duration = Duration::from_millis(16);
+
+
+/// Runs a [`System`] without a window or gpu, so that widget behaviour can be tested.
+/// Input is injected between frames and applies to the next call to [`TestHarness::run`].
+pub struct TestHarness {
+	pub system: System,
+	pub painter: Painter,
+
+	/// Created on first call to [`TestHarness::render`] or [`TestHarness::create_texture`].
+	software_renderer: Option<SoftwareRenderer>,
+
+	now: Instant,
+}
+
+impl TestHarness {
+	pub fn new(viewport_size: Vec2i) -> Self {
+		let mut system = System::new();
+		system.set_size(viewport_size);
+
+		TestHarness {
+			system,
+			painter: Painter::new(),
+			software_renderer: None,
+			now: Instant::now(),
+		}
+	}
+
+	/// Run a single frame, returning whatever `build_ui` returns. Time advances by one frame afterwards.
+	pub fn run<R>(&mut self, build_ui: impl FnOnce(&Ui<'_>) -> R) -> R {
+		let mut result = None;
+
+		self.painter.clear();
+		self.system.run_at(self.now, &mut self.painter, |ui| {
+			result = Some(build_ui(ui));
+		});
+
+		self.system.prepare_next_frame();
+		self.now += FRAME_DURATION;
+
+		result.expect("build_ui wasn't called")
+	}
+
+	pub fn advance_time(&mut self, duration: Duration) {
+		self.now += duration;
+	}
+
+	pub fn now(&self) -> Instant {
+		self.now
+	}
+
+	pub fn send(&mut self, event: InputEvent) -> SendEventResponse {
+		self.system.input.send_input_event(event, self.now)
+	}
+
+	pub fn move_cursor(&mut self, position: Vec2) {
+		self.send(InputEvent::CursorMoved { x: position.x, y: position.y });
+	}
+
+	pub fn press(&mut self, button: MouseButton) {
+		self.send(InputEvent::MouseButton { button, pressed: true });
+	}
+
+	pub fn release(&mut self, button: MouseButton) {
+		self.send(InputEvent::MouseButton { button, pressed: false });
+	}
+
+	/// Move the cursor to `position` and click the left mouse button, all before the next frame.
+	pub fn click(&mut self, position: Vec2) {
+		self.move_cursor(position);
+		self.press(MouseButton::Left);
+		self.release(MouseButton::Left);
+	}
+
+	/// Click the middle of a widget's box, as of the last frame.
+	pub fn click_widget(&mut self, widget_id: WidgetId) {
+		let center = self.box_center(widget_id);
+		self.click(center);
+	}
+
+	pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+		self.send(InputEvent::Modifiers(modifiers));
+	}
+
+	/// Press and release a key without generating any text.
+	pub fn tap_key(&mut self, key: impl Into<Key>) {
+		let key = key.into();
+		self.send(InputEvent::Key { key: key.clone(), text: None, pressed: true, repeat: false });
+		self.send(InputEvent::Key { key, text: None, pressed: false, repeat: false });
+	}
+
+	/// Press and release a key for each character of `text`.
+	pub fn type_text(&mut self, text: &str) {
+		for ch in text.chars() {
+			let key = Key::Character(SmolStr::new(ch.encode_utf8(&mut [0; 4])));
+			self.send(InputEvent::Key { key: key.clone(), text: Some(ch.to_string()), pressed: true, repeat: false });
+			self.send(InputEvent::Key { key, text: None, pressed: false, repeat: false });
+		}
+	}
+
+	pub fn layout(&self, widget_id: WidgetId) -> &Layout {
+		self.system.widget_layout(widget_id)
+			.expect("Widget wasn't laid out last frame")
+	}
+
+	pub fn box_center(&self, widget_id: WidgetId) -> Vec2 {
+		let bounds = self.layout(widget_id).box_bounds;
+		(bounds.min + bounds.max) / 2.0
+	}
+
+	pub fn hovered(&self) -> Option<WidgetId> {
+		self.system.input.hovered_widget
+	}
+
+	pub fn active(&self) -> Option<WidgetId> {
+		self.system.input.active_widget
+	}
+
+	pub fn focused(&self) -> Option<WidgetId> {
+		self.system.input.focus_widget
+	}
+
+	/// Number of triangles emitted by the last frame.
+	pub fn num_triangles(&self) -> usize {
+		self.painter.geometry.indices.len() / 3
+	}
+
+	/// Compare the layout of the last frame against the checked in `snapshots/{name}.txt`, panicking with a diff if
+	/// anything moved. Run with `UPDATE_SNAPSHOTS=1` to accept changes or to create new snapshots.
+	// No snapshots are checked in yet.
+	#[allow(dead_code)]
+	pub fn assert_layout_snapshot(&self, name: &str) {
+		let actual = self.system.layout_snapshot();
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots").join(format!("{name}.txt"));
+
+		if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+			std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+			std::fs::write(&path, &actual).unwrap();
+			return
+		}
+
+		let expected = match std::fs::read_to_string(&path) {
+			Ok(expected) => expected.replace("\r\n", "\n"),
+			Err(err) => panic!("Failed to read layout snapshot '{}': {err}\nRun with UPDATE_SNAPSHOTS=1 to create it", path.display()),
+		};
+
+		if expected != actual {
+			panic!("Layout snapshot '{name}' changed:\n{}\nRun with UPDATE_SNAPSHOTS=1 to accept", diff_lines(&expected, &actual));
+		}
+	}
+
+	/// Rasterize the last frame on the cpu, for comparing against golden images.
+	pub fn render(&mut self) -> ::image::RgbaImage {
+		let renderer = self.software_renderer.get_or_insert_with(SoftwareRenderer::new);
+		renderer.update_text_atlas(self.system.text_atlas.get_mut());
+		renderer.render(&self.painter, &self.system.viewport)
+	}
+
+	/// Create a texture that [`TestHarness::render`] can sample from. See [`SoftwareRenderer::create_texture`].
+	pub fn create_texture(&mut self, size: Vec2i, rgba_data: &[u8]) -> TextureHandle {
+		self.software_renderer.get_or_insert_with(SoftwareRenderer::new)
+			.create_texture(size, rgba_data)
+	}
+}
+
+
+/// Line diff of two texts, with removed lines prefixed by '-' and added lines by '+'.
+fn diff_lines(expected: &str, actual: &str) -> String {
+	let expected: Vec<&str> = expected.lines().collect();
+	let actual: Vec<&str> = actual.lines().collect();
+
+	// Longest common subsequence lengths of every pair of suffixes
+	let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+	for i in (0..expected.len()).rev() {
+		for j in (0..actual.len()).rev() {
+			lcs[i][j] = match expected[i] == actual[j] {
+				true => lcs[i + 1][j + 1] + 1,
+				false => lcs[i + 1][j].max(lcs[i][j + 1]),
+			};
+		}
+	}
+
+	let mut diff = String::new();
+	let (mut i, mut j) = (0, 0);
+
+	while i < expected.len() || j < actual.len() {
+		if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+			diff += &format!("  {}\n", expected[i]);
+			i += 1;
+			j += 1;
+		} else if j < actual.len() && (i == expected.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+			diff += &format!("+ {}\n", actual[j]);
+			j += 1;
+		} else {
+			diff += &format!("- {}\n", expected[i]);
+			i += 1;
+		}
+	}
+
+	diff
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn harness() -> TestHarness {
+		TestHarness::new(Vec2i::new(400, 300))
+	}
+
+	#[test]
+	fn hovering_button() {
+		let mut harness = harness();
+
+		let button = harness.run(|ui| ui.button("Hello").widget_id);
+		assert_eq!(harness.hovered(), None);
+
+		harness.move_cursor(harness.box_center(button));
+		harness.run(|ui| { ui.button("Hello"); });
+		assert_eq!(harness.hovered(), Some(button));
+	}
+
+	#[test]
+	fn clicking_button() {
+		let mut harness = harness();
+
+		let (button, clicked) = harness.run(|ui| {
+			let button = ui.button("Hello");
+			(button.widget_id, button.is_clicked())
+		});
+		assert!(!clicked);
+
+		harness.click_widget(button);
+
+		let clicked = harness.run(|ui| ui.button("Hello").is_clicked());
+		assert!(clicked);
+
+		// Clicks only last a frame
+		let clicked = harness.run(|ui| ui.button("Hello").is_clicked());
+		assert!(!clicked);
+	}
+
+	#[test]
+	fn clicking_focuses_and_clicking_elsewhere_unfocuses() {
+		let mut harness = harness();
+
+		let button = harness.run(|ui| ui.button("Hello").widget_id);
+
+		harness.click_widget(button);
+		harness.run(|ui| { ui.button("Hello"); });
+		assert_eq!(harness.focused(), Some(button));
+
+		harness.click(Vec2::new(390.0, 290.0));
+		harness.run(|ui| { ui.button("Hello"); });
+		assert_eq!(harness.focused(), None);
+	}
+
+	#[test]
+	fn tab_moves_focus_in_build_order() {
+		let mut harness = harness();
+
+		fn build(ui: &Ui<'_>) -> [WidgetId; 2] {
+			[ui.button("One").widget_id, ui.button("Two").widget_id]
+		}
+
+		let [one, two] = harness.run(build);
+
+		harness.tap_key(NamedKey::Tab);
+		harness.run(build);
+		assert_eq!(harness.focused(), Some(one));
+
+		harness.tap_key(NamedKey::Tab);
+		harness.run(build);
+		assert_eq!(harness.focused(), Some(two));
+
+		// Wraps around
+		harness.tap_key(NamedKey::Tab);
+		harness.run(build);
+		assert_eq!(harness.focused(), Some(one));
+
+		harness.set_modifiers(ModifiersState::SHIFT);
+		harness.tap_key(NamedKey::Tab);
+		harness.run(build);
+		assert_eq!(harness.focused(), Some(two));
+	}
+
+	#[test]
+	fn keyboard_activates_focused_button() {
+		let mut harness = harness();
+
+		let button = harness.run(|ui| ui.button("Hello").request_focus().widget_id);
+		harness.run(|ui| { ui.button("Hello"); });
+		assert_eq!(harness.focused(), Some(button));
+
+		harness.tap_key(NamedKey::Enter);
+		let clicked = harness.run(|ui| ui.button("Hello").is_clicked());
+		assert!(clicked);
+	}
+
+	#[test]
+	fn typing_into_focused_text_edit() {
+		let mut harness = harness();
+		let mut text = String::new();
+
+		harness.run(|ui| { ui.text_edit(&mut text).request_focus(); });
+		harness.run(|ui| { ui.text_edit(&mut text); });
+
+		harness.type_text("hi!");
+		harness.run(|ui| { ui.text_edit(&mut text); });
+
+		assert_eq!(text, "hi!");
+	}
+
+	#[test]
+	fn filled_widgets_emit_geometry() {
+		let mut harness = harness();
+
+		harness.run(|_| {});
+		let empty_triangles = harness.num_triangles();
+
+		harness.run(|ui| { ui.button(""); });
+		assert!(harness.num_triangles() > empty_triangles);
+	}
+
+	#[test]
+	fn diff_lines_marks_added_and_removed_lines() {
+		let diff = diff_lines("a\nb\nc\n", "a\nc\nd\n");
+		assert_eq!(diff, "  a\n- b\n  c\n+ d\n");
+	}
+
+	#[test]
+	fn clicking_board_leaves_no_stroke() {
+		let mut harness = harness();
+
+		let board = harness.run(|ui| ui.board().widget_id);
+
+		harness.click_widget(board);
+		harness.run(|ui| { ui.board(); });
+		let num_strokes = harness.run(|ui| ui.board().state_or_default().strokes.len());
+		assert_eq!(num_strokes, 0);
+	}
+
+	#[test]
+	fn dragging_on_board_adds_a_stroke() {
+		let mut harness = harness();
+
+		let board = harness.run(|ui| ui.board().widget_id);
+		let start = harness.box_center(board);
+
+		harness.move_cursor(start);
+		harness.press(MouseButton::Left);
+		harness.run(|ui| { ui.board(); });
+
+		for step in 1..=4 {
+			harness.move_cursor(start + Vec2::new(step as f32 * 10.0, 0.0));
+			harness.run(|ui| { ui.board(); });
+		}
+
+		harness.release(MouseButton::Left);
+		harness.run(|ui| { ui.board(); });
+		let num_strokes = harness.run(|ui| ui.board().state_or_default().strokes.len());
+		assert_eq!(num_strokes, 1);
+	}
+
+	#[test]
+	fn files_dropped_without_cursor_events_fall_back_to_target_center() {
+		let mut harness = harness();
+
+		let board = harness.run(|ui| ui.board().widget_id);
+		let center = harness.box_center(board);
+
+		// Leave from somewhere that isn't the centre, so a stale cursor position would be noticed
+		harness.move_cursor(center + Vec2::new(20.0, 10.0));
+		harness.run(|ui| { ui.board(); });
+		harness.send(InputEvent::CursorLeft);
+
+		let path = std::path::PathBuf::from("image.png");
+		harness.send(InputEvent::HoveredFile(path.clone()));
+		harness.send(InputEvent::DroppedFile(path.clone()));
+		assert!(harness.system.input.file_drop_position.is_none());
+
+		let (files, position) = harness.run(|ui| ui.board().file_drop_target()).expect("Board didn't take the files");
+		assert_eq!(files, [path]);
+		assert_eq!((position.x, position.y), (center.x, center.y));
+	}
+
+	#[test]
+	fn replaying_a_saved_recording_reproduces_state() {
+		let mut harness = harness();
+		let mut value = 0.5;
+
+		harness.system.input.start_recording();
+
+		let slider = harness.run(|ui| ui.slider(&mut value).widget_id);
+
+		harness.click_widget(slider);
+		harness.run(|ui| { ui.slider(&mut value); });
+
+		harness.tap_key(NamedKey::End);
+		harness.run(|ui| { ui.slider(&mut value); });
+
+		harness.tap_key(NamedKey::PageDown);
+		harness.run(|ui| { ui.slider(&mut value); });
+
+		assert_eq!(harness.focused(), Some(slider));
+		assert_ne!(value, 0.5);
+
+		let recording = harness.system.input.stop_recording().unwrap();
+
+		let path = std::env::temp_dir().join(format!("ui-recording-{}.jsonl", std::process::id()));
+		recording.save(&path).unwrap();
+		let loaded = InputRecording::load(&path);
+		std::fs::remove_file(&path).unwrap();
+
+		let loaded = loaded.unwrap();
+		assert_eq!(loaded.num_frames(), recording.num_frames());
+
+		let mut system = System::new();
+		let mut painter = Painter::new();
+		let mut replayed_value = 0.5;
+
+		InputReplay::new(loaded).run_to_end(&mut system, &mut painter, |ui| { ui.slider(&mut replayed_value); });
+
+		assert_eq!(replayed_value, value);
+		assert_eq!(system.input.focus_widget, harness.focused());
+		assert_eq!(system.input.hovered_widget, harness.hovered());
+	}
+
+	#[test]
+	fn spinner_keeps_requesting_frames() {
+		let mut harness = harness();
+
+		for _ in 0..3 {
+			harness.run(|ui| { ui.progress_bar(0.5); });
+		}
+		assert!(!harness.system.should_redraw());
+
+		for _ in 0..3 {
+			harness.run(|ui| { ui.spinner(); });
+			assert!(harness.system.should_redraw());
+		}
+	}
+
+	#[test]
+	fn image_is_sized_from_its_texture_and_drawn_with_it() {
+		let mut harness = harness();
+
+		let red = [255, 0, 0, 255].repeat(4 * 2);
+		let texture = harness.create_texture(Vec2i::new(4, 2), &red);
+
+		let image = harness.run(|ui| ui.image(texture).with_constraints(|c| c.set_width(80.0)).widget_id);
+
+		let bounds = harness.layout(image).content_bounds;
+		assert_eq!((bounds.width(), bounds.height()), (80.0, 40.0));
+
+		let center = harness.box_center(image);
+		let rendered = harness.render();
+		assert_eq!(rendered.get_pixel(center.x as u32, center.y as u32).0, [255, 0, 0, 255]);
+	}
+
+	fn build_splitter(ui: &Ui<'_>) -> (Vec<f32>, [WidgetId; 2]) {
+		let mut panes = [None; 2];
+
+		let splitter = ui.horizontal_splitter(|splitter| {
+			panes[0] = Some(splitter.pane(|| {}).with_constraints(|c| c.min_width.set(100.0)).widget_id);
+			panes[1] = Some(splitter.pane(|| {}).widget_id);
+		});
+
+		(splitter.ratios(), panes.map(Option::unwrap))
+	}
+
+	/// Centre of the divider between two panes, as of the last frame.
+	fn divider_center(harness: &TestHarness, [left, right]: [WidgetId; 2]) -> Vec2 {
+		Vec2::new(
+			(harness.layout(left).margin_bounds.max.x + harness.layout(right).margin_bounds.min.x) / 2.0,
+			harness.box_center(left).y,
+		)
+	}
+
+	#[test]
+	fn dragging_splitter_divider_respects_pane_limits() {
+		let mut harness = harness();
+
+		// Pane lengths take a frame to catch up with the splitter's size
+		let (_, panes) = harness.run(build_splitter);
+		harness.run(build_splitter);
+
+		let start = divider_center(&harness, panes);
+		harness.move_cursor(start);
+		harness.press(MouseButton::Left);
+		harness.run(build_splitter);
+
+		for step in 1..=4 {
+			harness.move_cursor(start - Vec2::new(step as f32 * 50.0, 0.0));
+			harness.run(build_splitter);
+		}
+
+		harness.release(MouseButton::Left);
+		harness.run(build_splitter);
+		harness.run(build_splitter);
+
+		let left_width = harness.layout(panes[0]).box_bounds.width();
+		assert!((left_width - 100.0).abs() < 1.0, "Left pane is {left_width} wide");
+	}
+
+	#[test]
+	fn double_clicking_splitter_divider_collapses_and_restores() {
+		let mut harness = harness();
+
+		let (even_ratios, panes) = harness.run(build_splitter);
+		harness.run(build_splitter);
+
+		let double_click_divider = |harness: &mut TestHarness| {
+			let position = divider_center(harness, panes);
+			harness.click(position);
+			harness.run(build_splitter);
+			harness.click(position);
+			harness.run(build_splitter);
+
+			// Make sure the next click isn't counted as part of this double click
+			harness.advance_time(Duration::from_secs(1));
+			harness.run(build_splitter).0
+		};
+
+		// Both panes are the same size, so the first one collapses down to its min width
+		let collapsed_ratios = double_click_divider(&mut harness);
+		assert_ne!(collapsed_ratios, even_ratios);
+
+		harness.run(build_splitter);
+		let left_width = harness.layout(panes[0]).box_bounds.width();
+		assert!((left_width - 100.0).abs() < 1.0, "Left pane is {left_width} wide");
+
+		let restored_ratios = double_click_divider(&mut harness);
+		assert_eq!(restored_ratios, even_ratios);
+	}
+
+	fn build_behind_dialog(ui: &Ui<'_>) -> (WidgetId, bool, Option<DialogResult>) {
+		let behind = ui.button("Behind");
+		let result = ui.message_dialog("Title", "Message", &["OK"]);
+		(behind.widget_id, behind.is_clicked(), result)
+	}
+
+	#[test]
+	fn modal_dialog_blocks_input_to_widgets_beneath_it() {
+		let mut harness = harness();
+
+		let (behind, _, _) = harness.run(build_behind_dialog);
+		harness.run(build_behind_dialog);
+
+		let behind_center = harness.box_center(behind);
+		harness.move_cursor(behind_center);
+		harness.run(build_behind_dialog);
+		assert_ne!(harness.hovered(), Some(behind));
+
+		// Clicking outside of the dialog surface dismisses it, but doesn't reach the button
+		harness.click(behind_center);
+		let (_, behind_clicked, result) = harness.run(build_behind_dialog);
+		assert!(!behind_clicked);
+		assert_eq!(result, Some(DialogResult::Cancelled));
+	}
+
+	#[test]
+	fn escape_cancels_dialog() {
+		let mut harness = harness();
+
+		let (_, _, result) = harness.run(build_behind_dialog);
+		assert_eq!(result, None);
+
+		harness.tap_key(NamedKey::Escape);
+		let (_, _, result) = harness.run(build_behind_dialog);
+		assert_eq!(result, Some(DialogResult::Cancelled));
+	}
+
+	#[test]
+	fn hovering_toast_pauses_its_timeout() {
+		let mut harness = harness();
+
+		harness.run(|ui| { ui.notify(Notification::new("Hello").with_duration(Duration::from_secs(1))); });
+
+		// Toasts are stacked in the bottom right corner
+		harness.move_cursor(Vec2::new(370.0, 260.0));
+		for _ in 0..20 {
+			harness.run(|_| {});
+		}
+		assert!(harness.hovered().is_some());
+
+		harness.advance_time(Duration::from_secs(5));
+		harness.run(|_| {});
+		assert!(harness.system.layout_snapshot().contains("Toast"));
+
+		harness.move_cursor(Vec2::new(10.0, 10.0));
+		harness.run(|_| {});
+		harness.advance_time(Duration::from_secs(2));
+		harness.run(|_| {});
+		harness.advance_time(Duration::from_secs(1));
+		harness.run(|_| {});
+		harness.run(|_| {});
+		assert!(!harness.system.layout_snapshot().contains("Toast"));
+	}
+
+	#[test]
+	fn tooltip_shows_after_delay_and_hides_on_press() {
+		let mut harness = harness();
+
+		fn build(ui: &Ui<'_>) -> WidgetId {
+			ui.button("Hello").tooltip("Tip").widget_id
+		}
+
+		fn has_tooltip(harness: &TestHarness) -> bool {
+			harness.system.layout_snapshot().contains("Tooltip")
+		}
+
+		let button = harness.run(build);
+
+		harness.move_cursor(harness.box_center(button));
+		harness.run(build);
+		assert!(!has_tooltip(&harness));
+
+		harness.advance_time(Duration::from_millis(200));
+		harness.run(build);
+		assert!(!has_tooltip(&harness));
+
+		harness.advance_time(Duration::from_millis(400));
+		harness.run(build);
+		assert!(has_tooltip(&harness));
+
+		harness.press(MouseButton::Left);
+		harness.run(build);
+		assert!(!has_tooltip(&harness));
+
+		// Stays hidden until the cursor leaves
+		harness.release(MouseButton::Left);
+		harness.advance_time(Duration::from_secs(1));
+		harness.run(build);
+		assert!(!has_tooltip(&harness));
+	}
+
+	#[test]
+	fn slider_responds_to_navigation_keys() {
+		let mut harness = harness();
+		let mut value = 0.5;
+
+		harness.run(|ui| { ui.slider(&mut value).request_focus(); });
+		harness.run(|ui| { ui.slider(&mut value); });
+
+		let mut press = |key: NamedKey| {
+			harness.tap_key(key);
+			harness.run(|ui| { ui.slider(&mut value); });
+			value
+		};
+
+		assert_eq!(press(NamedKey::End), 1.0);
+		assert!((press(NamedKey::PageDown) - 0.9).abs() < 0.0001);
+		assert_eq!(press(NamedKey::Home), 0.0);
+		assert!((press(NamedKey::PageUp) - 0.1).abs() < 0.0001);
+		assert!((press(NamedKey::ArrowRight) - 0.11).abs() < 0.0001);
+
+		// Values are clamped to the slider's range
+		press(NamedKey::Home);
+		assert_eq!(press(NamedKey::PageDown), 0.0);
+	}
+
+	fn build_drag_and_drop(ui: &Ui<'_>) -> ([WidgetId; 2], Option<u32>) {
+		let source = ui.button("Source").drag_source(|| 42u32);
+		let target = ui.button("Target");
+		let dropped = target.drop_target::<u32>();
+
+		([source.widget_id, target.widget_id], dropped)
+	}
+
+	/// Press on the source and drag over to the target over a few frames, without releasing.
+	fn drag_to_target(harness: &mut TestHarness) -> WidgetId {
+		let ([source, target], _) = harness.run(build_drag_and_drop);
+
+		let start = harness.box_center(source);
+		let end = harness.box_center(target);
+
+		harness.move_cursor(start);
+		harness.press(MouseButton::Left);
+		harness.run(build_drag_and_drop);
+
+		for step in 1..=4 {
+			harness.move_cursor(start + (end - start) * (step as f32 / 4.0));
+			harness.run(build_drag_and_drop);
+		}
+
+		target
+	}
+
+	#[test]
+	fn drag_and_drop_delivers_payload_on_release() {
+		let mut harness = harness();
+
+		let target = drag_to_target(&mut harness);
+		assert_eq!(harness.hovered(), Some(target));
+
+		let is_dragging = harness.run(|ui| {
+			build_drag_and_drop(ui);
+			ui.is_dragging()
+		});
+		assert!(is_dragging);
+
+		harness.release(MouseButton::Left);
+		let (_, dropped) = harness.run(build_drag_and_drop);
+		assert_eq!(dropped, Some(42));
+	}
+
+	#[test]
+	fn escape_cancels_drag() {
+		let mut harness = harness();
+
+		drag_to_target(&mut harness);
+
+		harness.tap_key(NamedKey::Escape);
+		harness.run(build_drag_and_drop);
+
+		harness.release(MouseButton::Left);
+		let (dropped, is_dragging) = harness.run(|ui| (build_drag_and_drop(ui).1, ui.is_dragging()));
+		assert_eq!(dropped, None);
+		assert!(!is_dragging);
+	}
+
+	#[test]
+	fn gestures_pan_and_zoom_hovered_board() {
+		let mut harness = harness();
+
+		// Returns the board's zoom, and the point on the board under `cursor`
+		fn build(ui: &Ui<'_>, cursor: Vec2) -> (WidgetId, f32, Vec2) {
+			let board = ui.board();
+			let zoom = board.state_or_default().zoom();
+			let board_pos = board.to_board_space(cursor).unwrap_or_else(Vec2::zero);
+			(board.widget_id, zoom, board_pos)
+		}
+
+		fn assert_near(a: Vec2, b: Vec2) {
+			assert!((a.x - b.x).abs() < 0.01 && (a.y - b.y).abs() < 0.01, "({}, {}) != ({}, {})", a.x, a.y, b.x, b.y);
+		}
+
+		let (board, _, _) = harness.run(|ui| build(ui, Vec2::zero()));
+		let cursor = harness.box_center(board);
+
+		harness.move_cursor(cursor);
+		harness.run(|ui| build(ui, cursor));
+		let (_, zoom, start_pos) = harness.run(|ui| build(ui, cursor));
+		assert_eq!(zoom, 1.0);
+
+		// Pinching zooms around the cursor
+		harness.send(InputEvent::Pinch { delta: 1.0 });
+		let (_, zoom, board_pos) = harness.run(|ui| build(ui, cursor));
+		assert!((zoom - 2.0).abs() < 0.001, "{zoom}");
+		assert_near(board_pos, start_pos);
+
+		// Touchpad scrolling pans by exactly the distance reported, in view space
+		harness.send(InputEvent::WheelPixels { x: 10.0, y: -20.0 });
+		let (_, zoom, board_pos) = harness.run(|ui| build(ui, cursor));
+		assert!((zoom - 2.0).abs() < 0.001, "{zoom}");
+		assert_near(board_pos, start_pos - Vec2::new(10.0, -20.0) / zoom);
+
+		// Ctrl+wheel zooms rather than scrolls
+		let (_, _, start_pos) = harness.run(|ui| build(ui, cursor));
+		harness.set_modifiers(ModifiersState::CONTROL);
+		harness.send(InputEvent::WheelLines { x: 0.0, y: -1.0 });
+		let (_, zoom, board_pos) = harness.run(|ui| build(ui, cursor));
+		assert!(zoom < 2.0, "{zoom}");
+		assert_near(board_pos, start_pos);
+	}
+
+	#[test]
+	fn inspector_adds_and_removes_its_panel() {
+		let mut harness = harness();
+
+		harness.system.toggle_inspector();
+		harness.run(|ui| { ui.button("Hello"); });
+		harness.run(|ui| { ui.button("Hello"); });
+		assert!(harness.system.layout_snapshot().contains("InspectorPanel"));
+
+		harness.system.toggle_inspector();
+		harness.run(|ui| { ui.button("Hello"); });
+		assert!(!harness.system.layout_snapshot().contains("InspectorPanel"));
+	}
+
+	#[test]
+	fn rendering_is_deterministic() {
+		let mut harness = harness();
+
+		harness.run(|ui| { ui.button("Hello"); });
+		let first = harness.render();
+
+		harness.run(|ui| { ui.button("Hello"); });
+		let second = harness.render();
+
+		assert!(first.pixels().any(|pixel| pixel.0[3] > 0));
+		assert_eq!(crate::software_renderer::count_differing_pixels(&first, &second, 0), Some(0));
+	}
+}
@@ -146,13 +146,50 @@ impl Hierarchy {
 		}
 	}
 
-	pub fn visit_breadth_first_with_cf<F>(&self, mut visit: F)
+	/// Returns whether `widget_id` is `ancestor_id` or is somewhere beneath it in the hierarchy.
+	pub fn is_descendant_of(&self, widget_id: WidgetId, ancestor_id: WidgetId) -> bool {
+		let mut current = Some(widget_id);
+
+		while let Some(current_id) = current {
+			if current_id == ancestor_id {
+				return true
+			}
+
+			current = self.nodes.get(&current_id).and_then(|node| node.parent_id);
+		}
+
+		false
+	}
+
+	pub fn visit_breadth_first_with_cf<F>(&self, visit: F)
+		where F: FnMut(WidgetId, &[WidgetId]) -> bool
+	{
+		self.visit_nodes_breadth_first_with_cf(&self.root_node.children, visit);
+	}
+
+	pub fn visit_subtree_breadth_first_with_cf<F>(&self, subtree_root: WidgetId, visit: F)
+		where F: FnMut(WidgetId, &[WidgetId]) -> bool
+	{
+		self.visit_nodes_breadth_first_with_cf(&[subtree_root], visit);
+	}
+
+	/// Visits each subtree of the root node in turn, breadth first.
+	/// Later subtrees are considered to be layered on top of earlier ones - e.g., for drawing and hit testing.
+	pub fn visit_layers_breadth_first<F>(&self, mut visit: F)
+		where F: FnMut(WidgetId, &[WidgetId])
+	{
+		for &layer_id in self.root_node.children.iter() {
+			self.visit_subtree_breadth_first_with_cf(layer_id, |p, cs| { visit(p, cs); true });
+		}
+	}
+
+	fn visit_nodes_breadth_first_with_cf<F>(&self, start_nodes: &[WidgetId], mut visit: F)
 		where F: FnMut(WidgetId, &[WidgetId]) -> bool
 	{
 		// TODO(pat.m): reuse intermediate visit structures
 		let mut visit_queue = VecDeque::new();
 
-		visit_queue.extend(self.root_node.children.iter().cloned());
+		visit_queue.extend(start_nodes.iter().copied());
 
 		while let Some(parent) = visit_queue.pop_front() {
 			let children = self.nodes[&parent].children.as_slice();
@@ -191,6 +228,20 @@ impl Hierarchy {
 		}
 	}
 
+	/// Preorder traversal of the subtrees rooted at `start_nodes` - i.e., the order widgets were added in.
+	pub fn visit_depth_first_from<F>(&self, start_nodes: &[WidgetId], mut visit: F)
+		where F: FnMut(WidgetId)
+	{
+		// TODO(pat.m): reuse intermediate visit structures
+		let mut visit_stack = Vec::new();
+		visit_stack.extend(start_nodes.iter().rev().copied());
+
+		while let Some(parent) = visit_stack.pop() {
+			visit(parent);
+			visit_stack.extend(self.nodes[&parent].children.iter().rev().copied());
+		}
+	}
+
 	/// Postorder traversal
 	pub fn visit_leaves_first<F>(&self, mut visit: F)
 		where F: FnMut(WidgetId)
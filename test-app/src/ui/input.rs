@@ -1,9 +1,15 @@
 use crate::prelude::*;
 
-use winit::event::{WindowEvent, KeyEvent, ElementState, MouseButton as WinitMouseButton};
+use winit::event::{WindowEvent, KeyEvent, ElementState, Ime, MouseScrollDelta, MouseButton as WinitMouseButton};
 use winit::dpi::PhysicalPosition;
 
-pub use winit::window::ResizeDirection;
+use std::time::{Duration, Instant};
+use std::path::PathBuf;
+
+use serde::{Serialize, Deserialize};
+
+pub use winit::window::{ResizeDirection, CursorIcon};
+pub use winit::keyboard::{Key, NamedKey, ModifiersState};
 
 
 #[derive(Default, Debug)]
@@ -16,12 +22,72 @@ pub struct Input {
 	pub active_widget: Option<ui::WidgetId>,
 	pub focus_widget: Option<ui::WidgetId>,
 
+	/// The widget that wheel and touchpad gestures are sent to - the hovered widget, or its nearest ancestor
+	/// with InputBehaviour::SCROLLABLE.
+	pub gesture_widget: Option<ui::WidgetId>,
+
+	/// Whether focus was last moved with the keyboard, and so should be indicated with a focus ring.
+	pub focus_visible: bool,
+
+	/// Focus requested by a widget during build. Applied once the widget has been registered.
+	pub requested_focus: Cell<Option<ui::WidgetId>>,
+
 	pub registered_widgets: HashMap<ui::WidgetId, RegisteredWidget>,
 	pub keyboard_input: Vec<KeyboardEvent>,
+	pub modifiers: ModifiersState,
+
+	/// Wheel and touchpad gestures received since the last frame, if any.
+	pub gestures: Option<GestureInput>,
+
+	/// The top-most root widget with InputBehaviour::MODAL, if any. Nothing beneath it will receive input.
+	pub modal_layer: Option<ui::WidgetId>,
+
+	/// The cursor icon requested by whatever is being hovered or interacted with.
+	pub cursor_icon: CursorIcon,
+
+	/// Width of the invisible border around the edge of the viewport that can be dragged to resize the window.
+	/// Zero disables it.
+	pub resize_border: f32,
+
+	/// Set while the cursor is over the resize border, in which case nothing else is hovered.
+	pub hovered_resize_direction: Option<ResizeDirection>,
+
+	pub click_settings: ClickSettings,
+
+	/// Files being dragged over the window from outside the app.
+	pub hovered_files: Vec<PathBuf>,
+
+	/// Files dropped onto the window since the last frame.
+	pub dropped_files: Vec<PathBuf>,
+
+	/// Whether the platform IME is currently enabled.
+	pub ime_enabled: bool,
+
+	/// Text being composed with the IME, which hasn't been committed yet.
+	pub ime_preedit: Option<ImePreedit>,
+
+	/// Where the focused text field's cursor was drawn this frame, so the IME candidate window can be placed near it.
+	pub ime_cursor_area: Cell<Option<Aabb2>>,
+
+	/// Where the cursor was when files were last hovered or dropped, if the platform reported it.
+	pub file_drop_position: Option<Vec2>,
+
+	/// Most platforms don't send cursor events while files are dragged over the window, in which case `cursor_pos`
+	/// is wherever the cursor last left the window and can't be used as the drop position.
+	cursor_moved_since_file_hover: bool,
+
+	/// Every cursor position received since the last frame, if requested.
+	pointer_samples: Vec<PointerSample>,
+	record_pointer_history: bool,
+	pointer_history_requested: Cell<bool>,
 
 	// The state of each mouse button as it is after all mouse events are processed.
 	button_states: [MouseButtonState; 5],
 
+	held_keys: HashSet<Key>,
+
+	recorder: Option<ui::InputRecorder>,
+
 	viewport: ui::Viewport,
 	timestamp: Wrapping<u32>,
 }
@@ -29,6 +95,11 @@ pub struct Input {
 impl Input {
 	pub fn reset(&mut self) {
 		self.keyboard_input.clear();
+		self.dropped_files.clear();
+		self.gestures = None;
+
+		self.pointer_samples.clear();
+		self.record_pointer_history = self.pointer_history_requested.take();
 		self.events_received_this_frame = false;
 		self.timestamp += 1;
 	}
@@ -37,36 +108,124 @@ impl Input {
 		self.viewport = viewport;
 	}
 
+	pub fn viewport(&self) -> ui::Viewport {
+		self.viewport
+	}
+
 	#[instrument(skip_all)]
 	pub fn send_event(&mut self, event: WindowEvent) -> SendEventResponse {
 		self.events_received_this_frame = true;
 
-		match event {
+		match self.translate_event(event) {
+			Some(event) => self.send_input_event(event, Instant::now()),
+			None => SendEventResponse::None,
+		}
+	}
+
+	/// Convert a window event into something that can be recorded and replayed without a window.
+	fn translate_event(&self, event: WindowEvent) -> Option<InputEvent> {
+		Some(match event {
 			WindowEvent::CursorMoved { position, .. } => {
 				let PhysicalPosition {x, y} = position.cast();
-				let cursor_pos = self.viewport.physical_to_view() * Vec2::new(x, y);
+				let Vec2{x, y} = self.viewport.physical_to_view() * Vec2::new(x, y);
+				InputEvent::CursorMoved { x, y }
+			}
+
+			WindowEvent::CursorLeft { .. } => InputEvent::CursorLeft,
+
+			WindowEvent::MouseInput { state, button, .. } => InputEvent::MouseButton {
+				button: MouseButton::try_from_winit(button)?,
+				pressed: state == ElementState::Pressed,
+			},
+
+			WindowEvent::MouseWheel { delta: MouseScrollDelta::LineDelta(x, y), .. } => InputEvent::WheelLines { x, y },
+
+			WindowEvent::MouseWheel { delta: MouseScrollDelta::PixelDelta(delta), .. } => {
+				let PhysicalPosition {x, y} = delta.cast();
+				let Vec2{x, y} = self.physical_delta_to_view(Vec2::new(x, y));
+				InputEvent::WheelPixels { x, y }
+			}
+
+			WindowEvent::PanGesture { delta, .. } => {
+				let Vec2{x, y} = self.physical_delta_to_view(Vec2::new(delta.x, delta.y));
+				InputEvent::Pan { x, y }
+			}
+			WindowEvent::PinchGesture { delta, .. } => InputEvent::Pinch { delta: delta as f32 },
+			WindowEvent::RotationGesture { delta, .. } => InputEvent::Rotation { degrees: delta },
+
+			WindowEvent::KeyboardInput { event, .. } => {
+				let KeyEvent { logical_key, text, state, repeat, .. } = event;
+
+				InputEvent::Key {
+					key: logical_key,
+					text: text.map(|text| text.to_string()),
+					pressed: state == ElementState::Pressed,
+					repeat,
+				}
+			}
+
+			WindowEvent::ModifiersChanged(modifiers) => InputEvent::Modifiers(modifiers.state()),
+
+			WindowEvent::Ime(ime) => InputEvent::Ime(ime),
+
+			WindowEvent::HoveredFile(path) => InputEvent::HoveredFile(path),
+			WindowEvent::HoveredFileCancelled => InputEvent::HoveredFileCancelled,
+			WindowEvent::DroppedFile(path) => InputEvent::DroppedFile(path),
+
+			WindowEvent::Focused(focused) => InputEvent::Focused(focused),
+
+			_ => return None,
+		})
+	}
+
+	/// Like converting a position, but without any offset since scroll and pan deltas are relative.
+	fn physical_delta_to_view(&self, delta: Vec2) -> Vec2 {
+		let physical_to_view = self.viewport.physical_to_view();
+		physical_to_view * delta - physical_to_view * Vec2::zero()
+	}
+
+	/// Handle an event that happened at `time`. Also used to replay recorded events.
+	pub fn send_input_event(&mut self, event: InputEvent, time: Instant) -> SendEventResponse {
+		self.events_received_this_frame = true;
+
+		if let Some(recorder) = &mut self.recorder {
+			recorder.record_event(&event, time);
+		}
+
+		match event {
+			InputEvent::CursorMoved { x, y } => {
+				let cursor_pos = Vec2::new(x, y);
 				self.cursor_pos = Some(cursor_pos);
+				self.cursor_moved_since_file_hover = true;
+
+				if !self.hovered_files.is_empty() {
+					self.file_drop_position = Some(cursor_pos);
+				}
 
-				// TODO(pat.m): queue mouse move events so we don't lose precision
-				// maybe this can be opt in?
+				if self.record_pointer_history {
+					self.pointer_samples.push(PointerSample {
+						position: cursor_pos,
+						time,
+					});
+				}
 			}
 
-			WindowEvent::CursorLeft { .. } => {
+			InputEvent::CursorLeft => {
 				self.cursor_pos = None;
 				self.hovered_widget = None;
 			}
 
-			WindowEvent::MouseInput { state: ElementState::Released, button, .. } => {
-				if let Some(button) = MouseButton::try_from_winit(button) {
-					self.button_state_mut(button).up_timestamp = self.timestamp.0;
-					self.active_widget = None;
-				}
+			InputEvent::MouseButton { button, pressed: false } => {
+				self.button_state_mut(button).up_timestamp = self.timestamp.0;
+				self.active_widget = None;
 			}
 
-			WindowEvent::MouseInput { state: ElementState::Pressed, button, .. } => {
-				let Some(button) = MouseButton::try_from_winit(button) else {
-					return SendEventResponse::None
-				};
+			InputEvent::MouseButton { button, pressed: true } => {
+				if button == MouseButton::Left
+					&& let Some(resize_direction) = self.cursor_pos.and_then(|pos| self.border_resize_direction(pos))
+				{
+					return SendEventResponse::DragResizeWindow(resize_direction)
+				}
 
 				// TODO(pat.m): shouldn't just use hovered_widget, but should
 				// pick first widget in hover stack that handles event
@@ -75,85 +234,469 @@ impl Input {
 				{
 					// If we hit a drag zone, then we _don't_ want to forward events to the rest of the ui
 					if reg.behaviour.contains(InputBehaviour::WINDOW_DRAG_ZONE) {
+						// The ui never sees this press, but it should still count towards multi-clicks
+						self.update_click_count(button, time);
+
+						if button == MouseButton::Left && self.click_count(button) == 2 {
+							return SendEventResponse::ToggleMaximizeWindow
+						}
+
 						return SendEventResponse::DragWindow
+
 					} else if reg.behaviour.contains(InputBehaviour::WINDOW_DRAG_RESIZE_ZONE) {
-						return SendEventResponse::DragResizeWindow(ResizeDirection::SouthEast)
+						return SendEventResponse::DragResizeWindow(self.zone_resize_direction(reg.bounds))
 					}
 				}
 
 				// No mouse downs without a position
 				if let Some(cursor_pos) = self.cursor_pos {
+					self.update_click_count(button, time);
 					self.button_state_mut(button).down_timestamp = self.timestamp.0;
 					self.button_state_mut(button).last_press_position = cursor_pos;
 				}
 			}
 
-			WindowEvent::KeyboardInput { event, .. } => {
-				if let Some(text) = event.text {
+			InputEvent::WheelLines { x, y } => {
+				let gestures = self.gestures_mut();
+				gestures.wheel_delta = gestures.wheel_delta + Vec2::new(x, y);
+			}
+
+			// Touchpads and precise wheels report exactly how far to move
+			InputEvent::WheelPixels { x, y } | InputEvent::Pan { x, y } => {
+				let gestures = self.gestures_mut();
+				gestures.pan_delta = gestures.pan_delta + Vec2::new(x, y);
+			}
+
+			// Positive deltas zoom in
+			InputEvent::Pinch { delta } => {
+				self.gestures_mut().zoom_factor *= 1.0 + delta;
+			}
+
+			// Counterclockwise
+			InputEvent::Rotation { degrees } => {
+				self.gestures_mut().rotation_delta += degrees.to_radians();
+			}
+
+			InputEvent::Key { key, text, pressed, repeat } => {
+				let modifiers = self.modifiers;
+
+				if pressed {
+					self.held_keys.insert(key.clone());
+					self.keyboard_input.push(KeyboardEvent::KeyDown { key, modifiers, repeat });
+				} else {
+					self.held_keys.remove(&key);
+					self.keyboard_input.push(KeyboardEvent::KeyUp { key, modifiers });
+				}
+
+				if let Some(text) = text {
 					self.keyboard_input.extend(text.chars().map(KeyboardEvent::Character));
 				}
 
-				// TODO(pat.m): key chords, modifiers
+				// TODO(pat.m): key chords
+			}
+
+			InputEvent::Modifiers(modifiers) => {
+				self.modifiers = modifiers;
 			}
 
-			_ => {}
+			InputEvent::Ime(ime) => match ime {
+				Ime::Enabled => {
+					self.ime_enabled = true;
+				}
+
+				Ime::Disabled => {
+					self.ime_enabled = false;
+					self.ime_preedit = None;
+				}
+
+				Ime::Preedit(text, cursor) => {
+					self.ime_preedit = (!text.is_empty()).then(|| ImePreedit { text, cursor });
+				}
+
+				// Committed text is treated just like typed text
+				Ime::Commit(text) => {
+					self.ime_preedit = None;
+					self.keyboard_input.extend(text.chars().map(KeyboardEvent::Character));
+				}
+			}
+
+			InputEvent::HoveredFile(path) => {
+				if self.hovered_files.is_empty() {
+					self.cursor_moved_since_file_hover = false;
+					self.file_drop_position = None;
+				}
+
+				self.hovered_files.push(path);
+			}
+
+			InputEvent::HoveredFileCancelled => {
+				self.hovered_files.clear();
+			}
+
+			// One event is sent per file
+			InputEvent::DroppedFile(path) => {
+				self.hovered_files.clear();
+				self.dropped_files.push(path);
+				self.file_drop_position = self.cursor_pos.filter(|_| self.cursor_moved_since_file_hover);
+			}
+
+			// We won't see key ups while unfocused
+			InputEvent::Focused(false) => {
+				self.held_keys.clear();
+			}
+
+			InputEvent::Focused(true) => {}
 		}
 
 		SendEventResponse::None
 	}
 
+	/// Start recording every event sent to this Input, along with frame boundaries, until [`Input::stop_recording`].
+	pub fn start_recording(&mut self) {
+		self.recorder = Some(ui::InputRecorder::new(Instant::now()));
+	}
+
+	pub fn stop_recording(&mut self) -> Option<ui::InputRecording> {
+		self.recorder.take().map(ui::InputRecorder::finish)
+	}
+
+	pub fn is_recording(&self) -> bool {
+		self.recorder.is_some()
+	}
+
+	/// Mark the start of a frame in the current recording, if any.
+	pub(super) fn record_frame(&mut self, now: Instant) {
+		let viewport_size = self.viewport.size;
+
+		if let Some(recorder) = &mut self.recorder {
+			recorder.record_frame(now, viewport_size);
+		}
+	}
+
+	fn gestures_mut(&mut self) -> &mut GestureInput {
+		let cursor_pos = self.cursor_pos;
+		self.gestures.get_or_insert_with(|| GestureInput::at(cursor_pos))
+	}
+
 	#[instrument(skip_all)]
 	pub fn process_events(&mut self, hierarchy: &ui::Hierarchy) {
 		if let Some(cursor_pos) = self.cursor_pos {
+			// Nothing is hovered unless something is under the cursor this frame
+			self.hovered_widget = None;
+
 			// TODO(pat.m): instead of just storing the last hovered widget, store a 'stack' of hovered widgets
-			hierarchy.visit_breadth_first(|widget_id, _| {
+			hierarchy.visit_layers_breadth_first(|widget_id, _| {
 				if let Some(widget_info) = self.registered_widgets.get(&widget_id)
-					&& widget_info.bounds.contains_point(cursor_pos)
+					&& widget_info.contains_point(cursor_pos)
 				{
 					self.hovered_widget = Some(widget_id);
 				}
 			});
 		}
 
-		// TODO(pat.m): this is completely bogus
-		if self.hovered_widget.is_some() {
-			if self.button_states.iter()
-				.any(|s| s.up_timestamp == self.timestamp.0)
+		// The resize border takes priority over anything beneath it, unless something is already being dragged
+		self.hovered_resize_direction = None;
+
+		if self.active_widget.is_none()
+			&& let Some(cursor_pos) = self.cursor_pos
+			&& let Some(resize_direction) = self.border_resize_direction(cursor_pos)
+		{
+			self.hovered_resize_direction = Some(resize_direction);
+			self.hovered_widget = None;
+		}
+
+		self.gesture_widget = self.hovered_widget.and_then(|widget_id| self.scrollable_ancestor(hierarchy, widget_id));
+
+		// Clicking moves focus to the clicked widget or whatever focusable widget contains it, and clicking
+		// anything else clears it.
+		if self.button_states.iter().any(|s| s.down_timestamp == self.timestamp.0) {
+			self.focus_widget = self.hovered_widget.and_then(|widget_id| self.focusable_ancestor(hierarchy, widget_id));
+			self.focus_visible = false;
+		}
+
+		if self.hovered_widget.is_some() && self.is_any_mouse_down() && self.active_widget.is_none() {
+			self.active_widget = self.hovered_widget;
+		}
+
+		let tab_direction = self.keyboard_input.iter()
+			.filter_map(|event| match event {
+				KeyboardEvent::KeyDown { key: Key::Named(NamedKey::Tab), modifiers, .. } => Some(modifiers.shift_key()),
+				_ => None,
+			})
+			.last();
+
+		if let Some(backwards) = tab_direction {
+			self.move_focus(hierarchy, backwards);
+
+			// Don't let widgets with focus see the tab as text input
+			self.keyboard_input.retain(|event| !matches!(event, KeyboardEvent::Character('\t')));
+		}
+
+		// Keep focus trapped within the top-most modal layer
+		if let Some(modal_layer) = self.modal_layer
+			&& let Some(focus_widget) = self.focus_widget
+			&& !hierarchy.is_descendant_of(focus_widget, modal_layer)
+		{
+			self.focus_widget = None;
+		}
+
+		// TODO(pat.m): from the input behaviour of each widget in the hovered widget stack, calculate the target of 
+		// any mouse click/keyboard events.
+	}
+
+	fn focusable_ancestor(&self, hierarchy: &ui::Hierarchy, widget_id: ui::WidgetId) -> Option<ui::WidgetId> {
+		let mut current = Some(widget_id);
+
+		while let Some(current_id) = current {
+			if self.registered_widgets.get(&current_id)
+				.is_some_and(|widget| widget.behaviour.contains(InputBehaviour::FOCUSABLE))
 			{
-				self.focus_widget = self.hovered_widget;
+				return Some(current_id)
 			}
 
-			if self.is_any_mouse_down() && self.active_widget.is_none() {
-				self.active_widget = self.hovered_widget;
+			current = hierarchy.parent(current_id);
+		}
+
+		None
+	}
+
+	fn scrollable_ancestor(&self, hierarchy: &ui::Hierarchy, widget_id: ui::WidgetId) -> Option<ui::WidgetId> {
+		let mut current = Some(widget_id);
+
+		while let Some(current_id) = current {
+			if self.registered_widgets.get(&current_id)
+				.is_some_and(|widget| widget.behaviour.contains(InputBehaviour::SCROLLABLE))
+			{
+				return Some(current_id)
 			}
+
+			current = hierarchy.parent(current_id);
 		}
 
-		// TODO(pat.m): from the input behaviour of each widget in the hovered widget stack, calculate the target of 
-		// any mouse click/keyboard events.
+		None
+	}
 
-		// TODO(pat.m): if a mouse up event occurs, set focus to whatever the top most focusable widget is
+	/// Move focus to the next or previous focusable widget in tree order, wrapping around at either end.
+	fn move_focus(&mut self, hierarchy: &ui::Hierarchy, backwards: bool) {
+		// Focus can't leave a modal layer
+		let start_nodes = match self.modal_layer {
+			Some(modal_layer) => vec![modal_layer],
+			None => hierarchy.children(None).to_vec(),
+		};
+
+		let mut focusable_widgets = Vec::new();
+		hierarchy.visit_depth_first_from(&start_nodes, |widget_id| {
+			if self.registered_widgets.get(&widget_id)
+				.is_some_and(|widget| widget.behaviour.contains(InputBehaviour::FOCUSABLE))
+			{
+				focusable_widgets.push(widget_id);
+			}
+		});
+
+		if focusable_widgets.is_empty() {
+			return
+		}
+
+		let current_index = self.focus_widget
+			.and_then(|focus_widget| focusable_widgets.iter().position(|&widget_id| widget_id == focus_widget));
+
+		let num_widgets = focusable_widgets.len();
+		let next_index = match (current_index, backwards) {
+			(Some(index), false) => (index + 1) % num_widgets,
+			(Some(index), true) => (index + num_widgets - 1) % num_widgets,
+			(None, false) => 0,
+			(None, true) => num_widgets - 1,
+		};
+
+		self.focus_widget = Some(focusable_widgets[next_index]);
+		self.focus_visible = true;
 	}
 
 	#[instrument(skip_all)]
 	pub fn register_handlers(&mut self, hierarchy: &ui::Hierarchy,
-		widgets: &HashMap<ui::WidgetId, ui::WidgetBox>, layouts: &ui::LayoutMap)
+		widgets: &HashMap<ui::WidgetId, ui::WidgetBox>, layouts: &ui::LayoutMap, app_style: &ui::AppStyle)
 	{
 		self.registered_widgets.clear();
+		self.modal_layer = None;
+
+		for &layer_id in hierarchy.children(None) {
+			// Nothing beneath a modal layer should receive input
+			if widgets[&layer_id].config.input.contains(InputBehaviour::MODAL) {
+				self.registered_widgets.clear();
+				self.modal_layer = Some(layer_id);
+			}
 
-		hierarchy.visit_breadth_first_with_cf(|widget_id, _| {
-			let widget_state = widgets.get(&widget_id).unwrap();
-			let behaviour = widget_state.config.input;
+			hierarchy.visit_subtree_breadth_first_with_cf(layer_id, |widget_id, _| {
+				let widget_state = widgets.get(&widget_id).unwrap();
+				let behaviour = widget_state.config.input;
 
-			let blocks_input_to_children = behaviour.contains(InputBehaviour::OPAQUE);
-			let receives_input = !behaviour.contains(InputBehaviour::TRANSPARENT);
+				let blocks_input_to_children = behaviour.contains(InputBehaviour::OPAQUE);
+				let receives_input = !behaviour.contains(InputBehaviour::TRANSPARENT);
 
-			if receives_input {
-				let bounds = layouts[&widget_id].box_bounds;
-				self.registered_widgets.insert(widget_id, RegisteredWidget {bounds, behaviour});
-			}
+				if receives_input {
+					let layout = &layouts[&widget_id];
+
+					let cursor = widget_state.config.cursor.or_else(|| {
+						if behaviour.contains(InputBehaviour::WINDOW_DRAG_RESIZE_ZONE) {
+							Some(resize_cursor(self.zone_resize_direction(layout.box_bounds)))
+						} else if behaviour.contains(InputBehaviour::WINDOW_DRAG_ZONE) {
+							Some(CursorIcon::Move)
+						} else {
+							None
+						}
+					});
+
+					let style = &widget_state.config.style;
+					let shape = widget_state.widget.hit_shape(layout).unwrap_or_else(|| {
+						// Widgets that draw a box should only be hit within its rounded corners
+						if style.fill.is_some() || style.outline.is_some() {
+							HitShape::RoundedRect(layout.box_bounds, style.rounding(app_style))
+						} else {
+							HitShape::Rect(layout.box_bounds)
+						}
+					});
+
+					// Only the visible part of a widget should receive input
+					let bounds = match layout.clip_rect {
+						Some(clip_rect) => intersect_rects(&layout.box_bounds, &clip_rect),
+						None => Some(layout.box_bounds),
+					};
 
-			!blocks_input_to_children
+					if let Some(bounds) = bounds {
+						self.registered_widgets.insert(widget_id, RegisteredWidget {bounds, shape, behaviour, cursor});
+					}
+				}
+
+				!blocks_input_to_children
+			});
+		}
+
+		if let Some(requested_focus) = self.requested_focus.take() {
+			self.focus_widget = Some(requested_focus);
+		}
+
+		// Drop any interaction with widgets that can no longer receive input, e.g., because they're now beneath a modal
+		if let Some(hovered_widget) = self.hovered_widget
+			&& !self.registered_widgets.contains_key(&hovered_widget)
+		{
+			self.hovered_widget = None;
+		}
+
+		if let Some(active_widget) = self.active_widget
+			&& !self.registered_widgets.contains_key(&active_widget)
+		{
+			self.active_widget = None;
+		}
+
+		if let Some(focus_widget) = self.focus_widget
+			&& !self.registered_widgets.contains_key(&focus_widget)
+		{
+			self.focus_widget = None;
+		}
+
+		self.update_cursor_icon(hierarchy);
+	}
+
+	/// Pick the cursor from the active widget, so it doesn't change mid-drag, or else the hovered widget.
+	fn update_cursor_icon(&mut self, hierarchy: &ui::Hierarchy) {
+		if self.active_widget.is_none()
+			&& let Some(resize_direction) = self.hovered_resize_direction
+		{
+			self.cursor_icon = resize_cursor(resize_direction);
+			return
+		}
+
+		let mut current = self.active_widget.or(self.hovered_widget);
+		let mut cursor_icon = None;
+
+		while let Some(widget_id) = current && cursor_icon.is_none() {
+			cursor_icon = self.registered_widgets.get(&widget_id).and_then(|widget| widget.cursor);
+			current = hierarchy.parent(widget_id);
+		}
+
+		self.cursor_icon = cursor_icon.unwrap_or_default();
+	}
+
+	/// Which edge or corner of the resize border `pos` is over, if any.
+	fn border_resize_direction(&self, pos: Vec2) -> Option<ResizeDirection> {
+		if self.resize_border <= 0.0 {
+			return None
+		}
+
+		let bounds = self.viewport.view_bounds();
+		let border = self.resize_border;
+
+		let near_x_edge = pos.x < bounds.min.x + border || pos.x > bounds.max.x - border;
+		let near_y_edge = pos.y < bounds.min.y + border || pos.y > bounds.max.y - border;
+
+		if !near_x_edge && !near_y_edge {
+			return None
+		}
+
+		// Corners extend a little way along each edge so they're easier to grab
+		let corner_size = border * 3.0;
+		let reach_x = if near_y_edge { corner_size } else { border };
+		let reach_y = if near_x_edge { corner_size } else { border };
+
+		let west = pos.x < bounds.min.x + reach_x;
+		let east = pos.x > bounds.max.x - reach_x;
+		let north = pos.y < bounds.min.y + reach_y;
+		let south = pos.y > bounds.max.y - reach_y;
+
+		Some(match (north, south, west, east) {
+			(true, _, true, _) => ResizeDirection::NorthWest,
+			(true, _, _, true) => ResizeDirection::NorthEast,
+			(_, true, true, _) => ResizeDirection::SouthWest,
+			(_, true, _, true) => ResizeDirection::SouthEast,
+			(true, _, _, _) => ResizeDirection::North,
+			(_, true, _, _) => ResizeDirection::South,
+			(_, _, true, _) => ResizeDirection::West,
+			_ => ResizeDirection::East,
+		})
+	}
+
+	/// Resize zones resize the window in the direction of whichever edge or corner of the viewport they're nearest.
+	fn zone_resize_direction(&self, zone_bounds: Aabb2) -> ResizeDirection {
+		let viewport_bounds = self.viewport.view_bounds();
+
+		let zone_center = (zone_bounds.min + zone_bounds.max) / 2.0;
+		let viewport_center = (viewport_bounds.min + viewport_bounds.max) / 2.0;
+		let offset = zone_center - viewport_center;
+
+		// Quantise to one of eight directions. Note that y points down.
+		let octant = (offset.y.atan2(offset.x) / std::f32::consts::FRAC_PI_4).round() as i32;
+
+		match octant.rem_euclid(8) {
+			0 => ResizeDirection::East,
+			1 => ResizeDirection::SouthEast,
+			2 => ResizeDirection::South,
+			3 => ResizeDirection::SouthWest,
+			4 => ResizeDirection::West,
+			5 => ResizeDirection::NorthWest,
+			6 => ResizeDirection::North,
+			_ => ResizeDirection::NorthEast,
+		}
+	}
+
+	/// Count a press towards the current click sequence if it is close enough in time and space to the last one,
+	/// or otherwise start a new sequence.
+	fn update_click_count(&mut self, button: MouseButton, now: Instant) {
+		let Some(cursor_pos) = self.cursor_pos else {
+			return
+		};
+
+		let settings = self.click_settings.clone();
+		let state = self.button_state_mut(button);
+
+		let continues_sequence = state.last_press_time.is_some_and(|last_press_time| {
+			let delta = cursor_pos - state.last_click_position;
+			delta.x.hypot(delta.y) <= settings.multi_click_distance
+				&& now.saturating_duration_since(last_press_time) <= settings.multi_click_interval
 		});
+
+		state.click_count = if continues_sequence { state.click_count + 1 } else { 1 };
+		state.last_press_time = Some(now);
+		state.last_click_position = cursor_pos;
 	}
 
 	fn button_state(&self, button: MouseButton) -> &MouseButtonState {
@@ -165,11 +708,185 @@ impl Input {
 	}
 }
 
+/// Keys that press buttons and flip checkboxes and toggles when they have focus.
+const ACTIVATION_KEYS: &[NamedKey] = &[NamedKey::Space, NamedKey::Enter];
+
+#[derive(Debug, Clone, Copy)]
+pub struct PointerSample {
+	pub position: Vec2,
+	pub time: Instant,
+}
+
+
+/// Builds a smoothed polyline through `points`, e.g., from [`Input::pointer_samples`].
+/// Points closer than `min_spacing` to the previous one are dropped to remove jitter, then corners are rounded off.
+/// The first and last points are kept as-is.
+pub fn smooth_polyline(points: impl IntoIterator<Item=Vec2>, min_spacing: f32) -> Vec<Vec2> {
+	let mut filtered: Vec<Vec2> = Vec::new();
+	let mut last_skipped = None;
+
+	for point in points {
+		if let Some(&last) = filtered.last() {
+			let delta = point - last;
+			if delta.x.hypot(delta.y) < min_spacing {
+				last_skipped = Some(point);
+				continue
+			}
+		}
+
+		filtered.push(point);
+		last_skipped = None;
+	}
+
+	// Make sure the polyline still ends where the input did
+	if let Some(last_point) = last_skipped {
+		if filtered.len() > 1 {
+			*filtered.last_mut().unwrap() = last_point;
+		} else {
+			// A second copy of the only point kept wouldn't make a line
+			let delta = last_point - filtered[0];
+			if delta.x != 0.0 || delta.y != 0.0 {
+				filtered.push(last_point);
+			}
+		}
+	}
+
+	if filtered.len() < 3 {
+		return filtered
+	}
+
+	// One round of Chaikin's corner cutting
+	let mut smoothed = Vec::with_capacity(filtered.len() * 2);
+	smoothed.push(filtered[0]);
+
+	for pair in filtered.windows(2) {
+		let (a, b) = (pair[0], pair[1]);
+		smoothed.push(a * 0.75 + b * 0.25);
+		smoothed.push(a * 0.25 + b * 0.75);
+	}
+
+	smoothed.push(filtered[filtered.len() - 1]);
+	smoothed
+}
+
+
+/// How far one line of mouse wheel movement should scroll.
+/// Fixed for now, though ideally it would follow the platform's wheel settings.
+pub const LINE_SCROLL_DISTANCE: f32 = 40.0;
+
+
+/// Wheel and touchpad gestures accumulated over a frame.
+#[derive(Debug, Clone)]
+pub struct GestureInput {
+	/// Movement of wheels that only report whole lines.
+	pub wheel_delta: Vec2,
+
+	/// How far content should move, from touchpad scrolling and pan gestures.
+	pub pan_delta: Vec2,
+
+	/// How much content should be scaled by. Greater than one zooms in.
+	pub zoom_factor: f32,
+
+	/// Counterclockwise rotation, in radians.
+	pub rotation_delta: f32,
+
+	/// Where the cursor was when the gesture started. Zooming and rotation should happen around this point.
+	pub centroid: Option<Vec2>,
+}
+
+impl GestureInput {
+	fn at(centroid: Option<Vec2>) -> Self {
+		GestureInput {
+			wheel_delta: Vec2::zero(),
+			pan_delta: Vec2::zero(),
+			zoom_factor: 1.0,
+			rotation_delta: 0.0,
+			centroid,
+		}
+	}
+
+	/// Wheel and pan movement combined, for widgets that only care about how far to scroll.
+	pub fn scroll_delta(&self) -> Vec2 {
+		self.pan_delta + self.wheel_delta * LINE_SCROLL_DISTANCE
+	}
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ClickSettings {
+	/// Max time between presses for them to count as a multi-click.
+	pub multi_click_interval: Duration,
+
+	/// Max distance the cursor can move between presses of a multi-click, or during a long press.
+	pub multi_click_distance: f32,
+
+	/// How long a button must be held without moving to count as a long press.
+	pub long_press_duration: Duration,
+}
+
+impl Default for ClickSettings {
+	fn default() -> Self {
+		// Common platform defaults, rather than whatever the user has actually configured
+		ClickSettings {
+			multi_click_interval: Duration::from_millis(400),
+			multi_click_distance: 4.0,
+			long_press_duration: Duration::from_millis(500),
+		}
+	}
+}
+
+
+fn resize_cursor(direction: ResizeDirection) -> CursorIcon {
+	match direction {
+		ResizeDirection::East => CursorIcon::EResize,
+		ResizeDirection::North => CursorIcon::NResize,
+		ResizeDirection::NorthEast => CursorIcon::NeResize,
+		ResizeDirection::NorthWest => CursorIcon::NwResize,
+		ResizeDirection::South => CursorIcon::SResize,
+		ResizeDirection::SouthEast => CursorIcon::SeResize,
+		ResizeDirection::SouthWest => CursorIcon::SwResize,
+		ResizeDirection::West => CursorIcon::WResize,
+	}
+}
+
+
+/// Everything [`Input`] responds to, independent of the windowing system so that it can be recorded and replayed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum InputEvent {
+	/// In view space.
+	CursorMoved { x: f32, y: f32 },
+	CursorLeft,
+
+	MouseButton { button: MouseButton, pressed: bool },
+
+	/// Movement of wheels that only report whole lines.
+	WheelLines { x: f32, y: f32 },
+
+	/// Touchpad scrolling, or precise wheel movement.
+	WheelPixels { x: f32, y: f32 },
+
+	Pan { x: f32, y: f32 },
+	Pinch { delta: f32 },
+	Rotation { degrees: f32 },
+
+	Key { key: Key, text: Option<String>, pressed: bool, repeat: bool },
+	Modifiers(ModifiersState),
+	Ime(Ime),
+
+	HoveredFile(PathBuf),
+	HoveredFileCancelled,
+	DroppedFile(PathBuf),
+
+	Focused(bool),
+}
+
+
 #[derive(Debug, Copy, Clone)]
 pub enum SendEventResponse {
 	None,
 	DragWindow,
 	DragResizeWindow(ResizeDirection),
+	ToggleMaximizeWindow,
 }
 
 
@@ -194,6 +911,89 @@ impl Input {
 		self.button_state(button).up_timestamp == self.timestamp.0
 	}
 
+	/// Returns whether a key was pressed since the last frame, including key repeats.
+	pub fn was_key_pressed(&self, key: impl Into<Key>) -> bool {
+		let key = key.into();
+
+		self.keyboard_input.iter()
+			.any(|event| matches!(event, KeyboardEvent::KeyDown{ key: event_key, .. } if *event_key == key))
+	}
+
+	pub fn is_key_down(&self, key: impl Into<Key>) -> bool {
+		self.held_keys.contains(&key.into())
+	}
+
+	/// Returns whether `widget_id` has focus and Space or Enter was pressed since the last frame.
+	pub fn was_activated_by_keyboard(&self, widget_id: ui::WidgetId) -> bool {
+		self.focus_widget == Some(widget_id)
+			&& self.keyboard_input.iter().any(|event| matches!(event,
+				KeyboardEvent::KeyDown{ key: Key::Named(key), repeat: false, .. } if ACTIVATION_KEYS.contains(key)))
+	}
+
+	/// Returns whether `widget_id` has focus and Space or Enter is being held, so it should appear pressed.
+	pub fn is_held_by_keyboard(&self, widget_id: ui::WidgetId) -> bool {
+		self.focus_widget == Some(widget_id)
+			&& ACTIVATION_KEYS.iter().any(|&key| self.is_key_down(key))
+	}
+
+	/// Iterate over named keys pressed since the last frame, including key repeats.
+	pub fn named_keys_pressed(&self) -> impl Iterator<Item=NamedKey> + '_ {
+		self.keyboard_input.iter()
+			.filter_map(|event| match event {
+				KeyboardEvent::KeyDown{ key: Key::Named(key), .. } => Some(*key),
+				_ => None,
+			})
+	}
+
+	/// Every cursor position received since the last frame, oldest first.
+	/// Always empty unless [`Input::request_pointer_history`] was called during the last frame.
+	pub fn pointer_samples(&self) -> &[PointerSample] {
+		&self.pointer_samples
+	}
+
+	/// Record every cursor position received before the next frame, rather than just the latest.
+	/// Needs to be requested each frame for as long as it is needed.
+	pub fn request_pointer_history(&self) {
+		self.pointer_history_requested.set(true);
+	}
+
+	/// Returns whether the focused widget accepts text input, and so the platform IME should be allowed.
+	pub fn wants_text_input(&self) -> bool {
+		self.focus_widget
+			.and_then(|widget_id| self.registered_widgets.get(&widget_id))
+			.is_some_and(|widget| widget.behaviour.contains(InputBehaviour::TEXT_INPUT))
+	}
+
+	/// Returns the wheel and touchpad gestures received this frame if they should go to `widget_id`.
+	pub fn gestures_for(&self, widget_id: ui::WidgetId) -> Option<&GestureInput> {
+		self.gestures.as_ref().filter(|_| self.gesture_widget == Some(widget_id))
+	}
+
+	pub fn is_file_hovered(&self) -> bool {
+		!self.hovered_files.is_empty()
+	}
+
+	/// Number of presses in the current click sequence - 2 for a double-click, 3 for a triple-click, etc.
+	/// Stays valid until the next press, so can be checked on release too.
+	pub fn click_count(&self, button: MouseButton) -> u32 {
+		self.button_state(button).click_count
+	}
+
+	/// If a button is being held without having moved much, the time at which it will become a long press.
+	pub fn long_press_deadline(&self, button: MouseButton) -> Option<Instant> {
+		let state = self.button_state(button);
+		if !state.is_down() {
+			return None
+		}
+
+		let delta = self.cursor_pos? - state.last_press_position;
+		if delta.x.hypot(delta.y) > self.click_settings.multi_click_distance {
+			return None
+		}
+
+		Some(state.last_press_time? + self.click_settings.long_press_duration)
+	}
+
 	pub fn mouse_drag_delta(&self, button: MouseButton) -> Option<Vec2> {
 		let state = self.button_state(button);
 		if state.is_down() {
@@ -205,7 +1005,7 @@ impl Input {
 }
 
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum MouseButton {
 	Left,
@@ -233,9 +1033,7 @@ bitflags! {
 	#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 	pub struct InputBehaviour : u32 {
 		// TODO(pat.m): handles left/right/etc mouse events
-		// TODO(pat.m): handles scroll events?
-		// TODO(pat.m): handles key events/ime?
-		// TODO(pat.m): draggable? focusable?
+		// TODO(pat.m): draggable?
 		// TODO(pat.m): capture on mouse down? maybe this should be implicit
 		// TODO(pat.m): ignores clipping?
 
@@ -245,6 +1043,18 @@ bitflags! {
 		/// Do not forward input events to children
 		const OPAQUE = 1<<1;
 
+		/// Block input to everything in layers beneath this one. Only meaningful for widgets at the root.
+		const MODAL = 1<<2;
+
+		/// Can receive keyboard focus, by clicking or with Tab/Shift+Tab.
+		const FOCUSABLE = 1<<3;
+
+		/// Accepts text input while focused, so the platform IME should be enabled.
+		const TEXT_INPUT = 1<<4;
+
+		/// Receives mouse wheel and touchpad gestures while it or any of its descendants are hovered.
+		const SCROLLABLE = 1<<5;
+
 		const WINDOW_DRAG_ZONE = 1<<10;
 		const WINDOW_DRAG_RESIZE_ZONE = 1<<11;
 	}
@@ -256,13 +1066,78 @@ impl Default for InputBehaviour {
 
 #[derive(Debug)]
 pub struct RegisteredWidget {
+	/// Box bounds clipped to the clip rects of all ancestors.
 	pub bounds: Aabb2,
+	pub shape: HitShape,
 	pub behaviour: InputBehaviour,
+	pub cursor: Option<CursorIcon>,
+}
+
+impl RegisteredWidget {
+	pub fn contains_point(&self, point: Vec2) -> bool {
+		self.bounds.contains_point(point) && self.shape.contains_point(point)
+	}
+}
+
+
+/// The area a widget accepts mouse input in. See [`ui::Widget::hit_shape`].
+#[derive(Debug, Copy, Clone)]
+pub enum HitShape {
+	Rect(Aabb2),
+	RoundedRect(Aabb2, painter::BorderRadii),
+	Circle { center: Vec2, radius: f32 },
+}
+
+impl HitShape {
+	pub fn contains_point(&self, point: Vec2) -> bool {
+		match *self {
+			HitShape::Rect(rect) => rect.contains_point(point),
+
+			HitShape::RoundedRect(rect, radii) => {
+				if !rect.contains_point(point) {
+					return false
+				}
+
+				let center_x = (rect.min.x + rect.max.x) / 2.0;
+				let center_y = (rect.min.y + rect.max.y) / 2.0;
+
+				let radius = match (point.x < center_x, point.y < center_y) {
+					(true, true) => radii.top_left,
+					(false, true) => radii.top_right,
+					(true, false) => radii.bottom_left,
+					(false, false) => radii.bottom_right,
+				};
+
+				// Distance from the edges of the nearest corner
+				let inset_x = (point.x - rect.min.x).min(rect.max.x - point.x);
+				let inset_y = (point.y - rect.min.y).min(rect.max.y - point.y);
+
+				if inset_x >= radius || inset_y >= radius {
+					return true
+				}
+
+				// Within the corner region, so test against the corner arc
+				(radius - inset_x).hypot(radius - inset_y) <= radius
+			}
+
+			HitShape::Circle { center, radius } => (point.x - center.x).hypot(point.y - center.y) <= radius,
+		}
+	}
+}
+
+fn intersect_rects(lhs: &Aabb2, rhs: &Aabb2) -> Option<Aabb2> {
+	let min = Vec2::new(lhs.min.x.max(rhs.min.x), lhs.min.y.max(rhs.min.y));
+	let max = Vec2::new(lhs.max.x.min(rhs.max.x), lhs.max.y.min(rhs.max.y));
+
+	(min.x < max.x && min.y < max.y).then(|| Aabb2::new(min, max))
 }
 
 #[derive(Debug, Default)]
 pub struct MouseButtonState {
 	pub last_press_position: Vec2,
+	pub last_press_time: Option<Instant>,
+	pub last_click_position: Vec2,
+	pub click_count: u32,
 	pub down_timestamp: u32,
 	pub up_timestamp: u32,
 }
@@ -273,8 +1148,18 @@ impl MouseButtonState {
 	}
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct ImePreedit {
+	pub text: String,
+
+	/// Byte range within `text` of the IME cursor or selection, if it should be shown.
+	pub cursor: Option<(usize, usize)>,
+}
+
 #[non_exhaustive]
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub enum KeyboardEvent {
 	Character(char),
+	KeyDown { key: Key, modifiers: ModifiersState, repeat: bool },
+	KeyUp { key: Key, modifiers: ModifiersState },
 }
\ No newline at end of file
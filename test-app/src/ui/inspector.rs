@@ -0,0 +1,257 @@
+use crate::ui::*;
+
+use std::fmt::{self, Write};
+
+
+const PANEL_WIDTH: f32 = 360.0;
+
+const MARGIN_COLOR: [f32; 4] = [1.0, 0.6, 0.2, 0.8];
+const BOX_COLOR: [f32; 4] = [1.0, 1.0, 0.4, 0.8];
+const CONTENT_COLOR: [f32; 4] = [0.3, 0.6, 1.0, 0.25];
+
+
+/// Debug tool showing how the widget under the cursor was configured and laid out, along with the whole hierarchy.
+/// Everything it shows is gathered at the end of a frame and displayed in the next.
+#[derive(Debug, Default)]
+pub struct InspectorState {
+	enabled: bool,
+
+	/// Root of the inspector's own widgets, so that they can be left out of what is inspected.
+	panel_id: Option<WidgetId>,
+
+	/// The last widget hovered outside of the panel.
+	inspected: Option<WidgetId>,
+
+	details: String,
+	tree: String,
+}
+
+impl InspectorState {
+	pub fn is_enabled(&self) -> bool {
+		self.enabled
+	}
+
+	pub(super) fn toggle(&mut self) {
+		self.enabled = !self.enabled;
+
+		if !self.enabled {
+			*self = InspectorState::default();
+		}
+	}
+
+	/// Gather everything the panel shows from the frame that was just laid out.
+	/// Returns whether anything changed, in which case another frame is needed to show it.
+	pub(super) fn update(&mut self, hierarchy: &Hierarchy, widgets: &HashMap<WidgetId, WidgetBox>,
+		constraints: &LayoutConstraintMap, layouts: &LayoutMap, input: &Input) -> bool
+	{
+		if !self.enabled {
+			return false
+		}
+
+		let panel_id = self.panel_id;
+		let is_in_panel = |widget_id| panel_id.is_some_and(|panel_id| hierarchy.is_descendant_of(widget_id, panel_id));
+
+		match input.hovered_widget {
+			// Keep showing the last widget while the cursor is over the panel, so that it can be read
+			Some(hovered_id) if is_in_panel(hovered_id) => {}
+			hovered_id => self.inspected = hovered_id,
+		}
+
+		if let Some(inspected_id) = self.inspected
+			&& !layouts.contains_key(&inspected_id)
+		{
+			self.inspected = None;
+		}
+
+		let mut details = String::new();
+		let mut tree = String::new();
+
+		self.write_details(&mut details, widgets, constraints, layouts)
+			.expect("Writing to a String can't fail");
+
+		self.write_tree(&mut tree, hierarchy, widgets, input)
+			.expect("Writing to a String can't fail");
+
+		let changed = details != self.details || tree != self.tree;
+		self.details = details;
+		self.tree = tree;
+
+		changed
+	}
+
+	fn write_details(&self, out: &mut String, widgets: &HashMap<WidgetId, WidgetBox>,
+		constraints: &LayoutConstraintMap, layouts: &LayoutMap) -> fmt::Result
+	{
+		let Some(widget_id) = self.inspected else {
+			return write!(out, "Hover a widget to inspect it")
+		};
+
+		let widget_box = &widgets[&widget_id];
+		let type_name = short_type_name((*widget_box.widget).type_name());
+
+		layout_snapshot::write_widget(out, "", &type_name, &constraints[&widget_id], &layouts[&widget_id])?;
+
+		let WidgetConfiguration { input, style, cursor } = &widget_box.config;
+
+		write!(out, "  | style fill ")?;
+		match &style.fill {
+			Some(fill) => write_color(out, fill)?,
+			None => write!(out, "none")?,
+		}
+
+		write!(out, ", outline ")?;
+		match &style.outline {
+			Some(outline) => {
+				write_color(out, &outline.color)?;
+				write!(out, " {}px", outline.width)?;
+			}
+
+			None => write!(out, "none")?,
+		}
+
+		match &style.rounding {
+			Some(radii) => writeln!(out, ", rounding {} {} {} {}", radii.top_left, radii.top_right, radii.bottom_left, radii.bottom_right)?,
+			None => writeln!(out, ", rounding default")?,
+		}
+
+		let input_names: Vec<&str> = input.iter_names().map(|(name, _)| name).collect();
+		match input_names.is_empty() {
+			true => write!(out, "  | input default")?,
+			false => write!(out, "  | input {}", input_names.join(" | "))?,
+		}
+
+		match cursor {
+			Some(cursor) => write!(out, ", cursor {cursor:?}"),
+			None => write!(out, ", cursor inherited"),
+		}
+	}
+
+	fn write_tree(&self, out: &mut String, hierarchy: &Hierarchy, widgets: &HashMap<WidgetId, WidgetBox>, input: &Input) -> fmt::Result {
+		let depths = layout_snapshot::widget_depths(hierarchy);
+
+		let root_ids: Vec<WidgetId> = hierarchy.root_node.children.iter()
+			.copied()
+			.filter(|&widget_id| Some(widget_id) != self.panel_id)
+			.collect();
+
+		let mut result = Ok(());
+
+		hierarchy.visit_depth_first_from(&root_ids, |widget_id| {
+			let indent = "  ".repeat(depths[&widget_id]);
+			let marker = if self.inspected == Some(widget_id) { "> " } else { "" };
+			let type_name = short_type_name((*widgets[&widget_id].widget).type_name());
+
+			let flags = [
+				(input.hovered_widget, " [hovered]"),
+				(input.active_widget, " [active]"),
+				(input.focus_widget, " [focused]"),
+			];
+
+			let flags: String = flags.into_iter()
+				.filter(|&(flagged_id, _)| flagged_id == Some(widget_id))
+				.map(|(_, flag)| flag)
+				.collect();
+
+			if result.is_ok() {
+				result = writeln!(out, "{indent}{marker}{type_name}{flags}");
+			}
+		});
+
+		result
+	}
+
+	/// Outline the bounds of the inspected widget, and whichever widgets are hovered, active and focused.
+	pub(super) fn draw_overlay(&self, painter: &mut Painter, layouts: &LayoutMap, input: &Input) {
+		if !self.enabled {
+			return
+		}
+
+		painter.set_line_width(1.0);
+
+		let highlights = [
+			(input.hovered_widget, Color::light_cyan()),
+			(input.active_widget, Color::light_green()),
+			(input.focus_widget, Color::light_red()),
+		];
+
+		for (widget_id, color) in highlights {
+			if let Some(layout) = widget_id.and_then(|widget_id| layouts.get(&widget_id)) {
+				painter.set_color(color);
+				painter.rect_outline(layout.box_bounds);
+			}
+		}
+
+		let Some(layout) = self.inspected.and_then(|widget_id| layouts.get(&widget_id)) else {
+			return
+		};
+
+		painter.set_color(CONTENT_COLOR);
+		painter.rect(layout.content_bounds);
+
+		painter.set_color(BOX_COLOR);
+		painter.rect_outline(layout.box_bounds);
+
+		painter.set_color(MARGIN_COLOR);
+		painter.rect_outline(layout.margin_bounds);
+	}
+}
+
+fn write_color(out: &mut String, color: &WidgetColor) -> fmt::Result {
+	match color {
+		WidgetColor::Role(role) => write!(out, "{role:?}"),
+		WidgetColor::Literal(color) => write!(out, "{color:?}"),
+	}
+}
+
+
+
+/// Root level layer holding the inspector panel, along the right edge of the viewport.
+#[derive(Debug)]
+pub struct InspectorPanel {}
+
+impl Widget for InspectorPanel {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Vertical);
+		ctx.constraints.padding.set_default(8.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.preferred_width.set_default(PANEL_WIDTH);
+
+		// The inspector shouldn't affect the minimum size of the window
+		ctx.constraints.min_width.set(0.0);
+		ctx.constraints.min_height.set(0.0);
+
+		if ctx.style.fill.is_none() {
+			ctx.style.set_fill(WidgetColorRole::SurfaceContainerHighest);
+		}
+
+		if ctx.style.outline.is_none() {
+			ctx.style.set_outline(WidgetColorRole::Outline);
+		}
+	}
+}
+
+
+
+impl Ui<'_> {
+	pub(super) fn build_inspector_layer(&self) {
+		let mut inspector = self.persistent_state.inspector.borrow_mut();
+		if !inspector.enabled {
+			return
+		}
+
+		let panel = self.add_widget_to(InspectorPanel{}, None);
+		inspector.panel_id = Some(panel.widget_id);
+
+		let viewport_width = self.input.viewport().size.x;
+		panel.constraints().margin.left.set((viewport_width - PANEL_WIDTH).max(0.0));
+
+		// Long hierarchies are clipped at the bottom of the window, since the panel doesn't scroll
+		self.with_parent(&panel, || {
+			self.text("Inspector (F12)");
+			self.text(inspector.details.clone());
+			self.text("Hierarchy");
+			self.text(inspector.tree.clone());
+		});
+	}
+}
@@ -0,0 +1,162 @@
+use crate::ui::*;
+
+use std::fmt::{self, Display, Write};
+
+
+const SNAPSHOT_HEADER: &str = "\
+# * marks constraints that were set explicitly, everything else was defaulted
+# box lengths are left right top bottom
+";
+
+
+/// Writes every widget in the hierarchy with its type, constraints and resolved layout, in build order.
+/// Meant to be stable between runs so that it can be diffed - see `System::layout_snapshot`.
+pub(super) fn write_layout_snapshot(out: &mut String, hierarchy: &Hierarchy, widgets: &HashMap<WidgetId, WidgetBox>,
+	constraints: &LayoutConstraintMap, layouts: &LayoutMap)
+{
+	out.push_str(SNAPSHOT_HEADER);
+
+	let depths = widget_depths(hierarchy);
+
+	hierarchy.visit_depth_first_from(&hierarchy.root_node.children, |widget_id| {
+		let indent = "  ".repeat(depths[&widget_id]);
+		let type_name = short_type_name((*widgets[&widget_id].widget).type_name());
+
+		write_widget(out, &indent, &type_name, &constraints[&widget_id], &layouts[&widget_id])
+			.expect("Writing to a String can't fail");
+	});
+}
+
+/// How far each widget is from the root of the hierarchy, with root level widgets at depth 0.
+pub(super) fn widget_depths(hierarchy: &Hierarchy) -> HashMap<WidgetId, usize> {
+	let mut depths = HashMap::new();
+
+	hierarchy.visit_breadth_first(|widget_id, children| {
+		let depth = depths.get(&widget_id).copied().unwrap_or(0);
+		depths.extend(children.iter().map(|&child_id| (child_id, depth + 1)));
+	});
+
+	depths
+}
+
+pub(super) fn write_widget(out: &mut String, indent: &str, type_name: &str, constraints: &LayoutConstraints, layout: &Layout) -> fmt::Result {
+	writeln!(out, "{indent}{type_name}")?;
+
+	let indent = format!("{indent}  | ");
+
+	write!(out, "{indent}box {}, content {}, margin {}",
+		Rect(layout.box_bounds), Rect(layout.content_bounds), Rect(layout.margin_bounds))?;
+
+	if let Some(clip_rect) = layout.clip_rect {
+		write!(out, ", clip {}", Rect(clip_rect))?;
+	}
+
+	writeln!(out)?;
+
+	writeln!(out, "{indent}width min {} pref {} max {}, policy {}",
+		Param(&constraints.min_width), Param(&constraints.preferred_width), Param(&constraints.max_width),
+		Param(&constraints.horizontal_size_policy))?;
+
+	writeln!(out, "{indent}height min {} pref {} max {}, policy {}",
+		Param(&constraints.min_height), Param(&constraints.preferred_height), Param(&constraints.max_height),
+		Param(&constraints.vertical_size_policy))?;
+
+	writeln!(out, "{indent}margin {}, padding {}", Lengths(&constraints.margin), Lengths(&constraints.padding))?;
+
+	writeln!(out, "{indent}axis {}, content align {}, self align {}",
+		Param(&constraints.layout_axis), Param(&constraints.content_alignment), Param(&constraints.self_alignment))
+}
+
+
+
+trait SnapshotValue {
+	fn fmt_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl SnapshotValue for f32 {
+	fn fmt_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.is_infinite() {
+			let sign = if *self < 0.0 { "-" } else { "" };
+			return write!(f, "{sign}inf")
+		}
+
+		// Round away float noise, and avoid printing -0
+		let value = (self * 100.0).round() / 100.0 + 0.0;
+		write!(f, "{value}")
+	}
+}
+
+impl SnapshotValue for SizingBehaviour {
+	fn fmt_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match (self.contains(SizingBehaviour::CAN_GROW), self.contains(SizingBehaviour::CAN_SHRINK)) {
+			(true, true) => "flexible",
+			(true, false) => "grow",
+			(false, true) => "shrink",
+			(false, false) => "fixed",
+		};
+
+		f.write_str(name)
+	}
+}
+
+impl SnapshotValue for Axis {
+	fn fmt_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Axis::Horizontal => f.write_str("horizontal"),
+			Axis::Vertical => f.write_str("vertical"),
+		}
+	}
+}
+
+impl SnapshotValue for Align {
+	fn fmt_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Align::Start => f.write_str("start"),
+			Align::Middle => f.write_str("middle"),
+			Align::End => f.write_str("end"),
+		}
+	}
+}
+
+
+struct Param<'a, T>(&'a WidgetParameter<T>);
+
+impl<T> Display for Param<'_, T>
+	where T: SnapshotValue + Copy
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.0.get().fmt_value(f)?;
+
+		if self.0.is_set() {
+			f.write_str("*")?;
+		}
+
+		Ok(())
+	}
+}
+
+struct Lengths<'a>(&'a BoxLengths);
+
+impl Display for Lengths<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let BoxLengths{left, right, top, bottom} = self.0;
+		write!(f, "{} {} {} {}", Param(left), Param(right), Param(top), Param(bottom))
+	}
+}
+
+struct Rect(Aabb2);
+
+impl Display for Rect {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let Rect(Aabb2{min, max}) = self;
+
+		f.write_str("(")?;
+		min.x.fmt_value(f)?;
+		f.write_str(", ")?;
+		min.y.fmt_value(f)?;
+		f.write_str(") ")?;
+		(max.x - min.x).fmt_value(f)?;
+		f.write_str("x")?;
+		(max.y - min.y).fmt_value(f)
+	}
+}
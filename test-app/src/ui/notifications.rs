@@ -0,0 +1,276 @@
+use crate::ui::*;
+
+use std::time::{Duration, Instant};
+
+// https://m3.material.io/components/snackbar/specs
+
+
+const DEFAULT_DURATION: Duration = Duration::from_secs(4);
+const TRANSITION_DURATION: Duration = Duration::from_millis(150);
+const MAX_VISIBLE_NOTIFICATIONS: usize = 4;
+
+// How far toasts slide in from the edge of the viewport
+const SLIDE_DISTANCE: f32 = 400.0;
+
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct NotificationId(u64);
+
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+	pub text: String,
+	pub action: Option<String>,
+	pub duration: Duration,
+}
+
+impl Notification {
+	pub fn new(text: impl Into<String>) -> Self {
+		Notification {
+			text: text.into(),
+			action: None,
+			duration: DEFAULT_DURATION,
+		}
+	}
+
+	pub fn with_action(self, label: impl Into<String>) -> Self {
+		Notification { action: Some(label.into()), ..self }
+	}
+
+	pub fn with_duration(self, duration: Duration) -> Self {
+		Notification { duration, ..self }
+	}
+}
+
+impl From<&str> for Notification {
+	fn from(text: &str) -> Self {
+		Notification::new(text)
+	}
+}
+
+impl From<String> for Notification {
+	fn from(text: String) -> Self {
+		Notification::new(text)
+	}
+}
+
+
+
+#[derive(Debug, Default)]
+pub struct NotificationQueue {
+	entries: Vec<NotificationEntry>,
+	next_id: u64,
+
+	/// Notifications whose action was triggered last frame.
+	activated: Vec<NotificationId>,
+
+	last_update: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct NotificationEntry {
+	id: NotificationId,
+	notification: Notification,
+
+	remaining: Duration,
+	dismissed: bool,
+
+	/// How far through its enter/exit transition this notification is. 0 is hidden, 1 is fully shown.
+	visibility: f32,
+
+	widget_id: Option<WidgetId>,
+}
+
+impl NotificationQueue {
+	pub fn push(&mut self, notification: Notification) -> NotificationId {
+		let id = NotificationId(self.next_id);
+		self.next_id += 1;
+
+		self.entries.push(NotificationEntry {
+			id,
+			remaining: notification.duration,
+			notification,
+			dismissed: false,
+			visibility: 0.0,
+			widget_id: None,
+		});
+
+		id
+	}
+
+	pub fn dismiss(&mut self, id: NotificationId) {
+		if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+			entry.dismissed = true;
+		}
+	}
+
+	pub fn was_action_triggered(&self, id: NotificationId) -> bool {
+		self.activated.contains(&id)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Advance timers and transitions. Returns when the queue next needs updating, if at all.
+	pub(super) fn update(&mut self, now: Instant, input: &Input, hierarchy: &Hierarchy) -> Option<Instant> {
+		let delta = match self.last_update {
+			Some(last_update) => now.saturating_duration_since(last_update),
+			None => Duration::ZERO,
+		};
+
+		self.last_update = Some(now);
+
+		let transition_delta = delta.as_secs_f32() / TRANSITION_DURATION.as_secs_f32();
+
+		let mut next_update = None;
+		let mut schedule_update = |time: Instant| {
+			next_update = Some(next_update.map_or(time, |next: Instant| next.min(time)));
+		};
+
+		for entry in self.entries.iter_mut().take(MAX_VISIBLE_NOTIFICATIONS) {
+			if entry.dismissed {
+				entry.visibility = (entry.visibility - transition_delta).max(0.0);
+				schedule_update(now);
+				continue
+			}
+
+			entry.visibility = (entry.visibility + transition_delta).min(1.0);
+			if entry.visibility < 1.0 {
+				schedule_update(now);
+			}
+
+			// Don't time out while the user is looking at it
+			let is_hovered = input.hovered_widget
+				.zip(entry.widget_id)
+				.is_some_and(|(hovered_id, widget_id)| hierarchy.is_descendant_of(hovered_id, widget_id));
+
+			if !is_hovered {
+				entry.remaining = entry.remaining.saturating_sub(delta);
+				entry.dismissed = entry.remaining.is_zero();
+				schedule_update(now + entry.remaining);
+			}
+		}
+
+		self.entries.retain(|entry| !entry.dismissed || entry.visibility > 0.0);
+
+		if self.entries.is_empty() {
+			self.last_update = None;
+		}
+
+		next_update
+	}
+}
+
+
+
+/// Root level layer that toasts are stacked in.
+#[derive(Debug)]
+pub struct NotificationLayer {}
+
+impl Widget for NotificationLayer {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Vertical);
+		ctx.constraints.content_alignment.set_default(Align::End);
+		ctx.constraints.padding.set_default(16.0);
+
+		// Notifications shouldn't affect the minimum size of the window
+		ctx.constraints.min_width.set(0.0);
+		ctx.constraints.min_height.set(0.0);
+
+		// Let input through to whatever is beneath, but not our children
+		*ctx.input |= InputBehaviour::TRANSPARENT;
+	}
+}
+
+
+#[derive(Debug)]
+pub struct Toast {}
+
+impl Widget for Toast {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Horizontal);
+		ctx.constraints.content_alignment.set_default(Align::Middle);
+
+		ctx.constraints.min_height.set_default(48.0);
+		ctx.constraints.max_width.set_default(560.0);
+
+		ctx.constraints.padding.set_horizontal(16.0);
+		ctx.constraints.padding.set_vertical(4.0);
+		ctx.constraints.margin.set_default(4.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		if ctx.style.fill.is_none() {
+			ctx.style.set_fill(WidgetColorRole::InverseSurface);
+		}
+	}
+}
+
+
+
+impl Ui<'_> {
+	/// Queue a notification to be shown as a toast.
+	pub fn notify(&self, notification: impl Into<Notification>) -> NotificationId {
+		self.trigger_redraw();
+		self.notifications.borrow_mut().push(notification.into())
+	}
+
+	pub fn dismiss_notification(&self, id: NotificationId) {
+		self.trigger_redraw();
+		self.notifications.borrow_mut().dismiss(id);
+	}
+
+	/// Returns whether the action button on a notification was clicked last frame.
+	pub fn was_notification_action_triggered(&self, id: NotificationId) -> bool {
+		self.notifications.borrow().was_action_triggered(id)
+	}
+
+	pub(super) fn build_notification_layer(&self) {
+		let mut queue = self.notifications.borrow_mut();
+		let NotificationQueue { entries, activated, .. } = &mut *queue;
+
+		activated.clear();
+
+		if entries.is_empty() {
+			return
+		}
+
+		let layer = self.add_widget_to(NotificationLayer{}, None);
+
+		self.with_parent(&layer, || {
+			self.spring(Axis::Vertical);
+
+			for entry in entries.iter_mut().take(MAX_VISIBLE_NOTIFICATIONS) {
+				let slide_t = Easing::EaseOutCubic.apply(entry.visibility);
+				let slide_offset = (1.0 - slide_t) * SLIDE_DISTANCE;
+
+				let toast = self.with_parent_widget(Toast{}, || {
+					self.text(entry.notification.text.clone());
+
+					if let Some(action) = &entry.notification.action {
+						let action_button = self.button(action.clone())
+							.with_style(|s| {
+								s.set_fill(WidgetColorRole::InverseSurface);
+								s.set_outline(WidgetColorRole::InversePrimary);
+							})
+							.with_constraints(|c| c.margin.left.set(16.0));
+
+						if !entry.dismissed && action_button.is_clicked() {
+							activated.push(entry.id);
+							entry.dismissed = true;
+						}
+					}
+				});
+
+				toast.constraints().margin.right.set(4.0 - slide_offset);
+				entry.widget_id = Some(toast.widget_id);
+			}
+		});
+
+		if !activated.is_empty() {
+			self.trigger_redraw();
+		}
+	}
+}
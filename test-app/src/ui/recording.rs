@@ -0,0 +1,161 @@
+use crate::ui::*;
+
+use serde::{Serialize, Deserialize};
+
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+
+/// One line of a recording. Times are relative to when recording started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RecordedEntry {
+	Event {
+		time: Duration,
+		event: InputEvent,
+	},
+
+	/// Marks the start of a frame - every event before it was received before that frame ran.
+	Frame {
+		time: Duration,
+		viewport_size: [f32; 2],
+	},
+}
+
+
+/// Collects everything sent to an [`Input`] while recording. See [`Input::start_recording`].
+#[derive(Debug)]
+pub struct InputRecorder {
+	start: Instant,
+	entries: Vec<RecordedEntry>,
+}
+
+impl InputRecorder {
+	pub(super) fn new(start: Instant) -> Self {
+		InputRecorder {
+			start,
+			entries: Vec::new(),
+		}
+	}
+
+	pub(super) fn record_event(&mut self, event: &InputEvent, time: Instant) {
+		self.entries.push(RecordedEntry::Event {
+			time: time.saturating_duration_since(self.start),
+			event: event.clone(),
+		});
+	}
+
+	pub(super) fn record_frame(&mut self, now: Instant, viewport_size: Vec2) {
+		self.entries.push(RecordedEntry::Frame {
+			time: now.saturating_duration_since(self.start),
+			viewport_size: [viewport_size.x, viewport_size.y],
+		});
+	}
+
+	pub(super) fn finish(self) -> InputRecording {
+		InputRecording {
+			entries: self.entries,
+		}
+	}
+}
+
+
+/// A sequence of input events and frame boundaries that can be saved, attached to bug reports and replayed
+/// with [`InputReplay`].
+#[derive(Debug, Clone, Default)]
+pub struct InputRecording {
+	pub entries: Vec<RecordedEntry>,
+}
+
+impl InputRecording {
+	pub fn num_frames(&self) -> usize {
+		self.entries.iter()
+			.filter(|entry| matches!(entry, RecordedEntry::Frame{..}))
+			.count()
+	}
+
+	/// Saves as json, one entry per line.
+	pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+		let mut writer = BufWriter::new(std::fs::File::create(path)?);
+
+		for entry in self.entries.iter() {
+			serde_json::to_writer(&mut writer, entry)?;
+			writer.write_all(b"\n")?;
+		}
+
+		writer.flush()?;
+		Ok(())
+	}
+
+	pub fn load(path: impl AsRef<Path>) -> anyhow::Result<InputRecording> {
+		let reader = BufReader::new(std::fs::File::open(path)?);
+		let mut entries = Vec::new();
+
+		for line in reader.lines() {
+			let line = line?;
+			if line.trim().is_empty() {
+				continue
+			}
+
+			entries.push(serde_json::from_str(&line)?);
+		}
+
+		Ok(InputRecording { entries })
+	}
+}
+
+
+/// Feeds a recording back into a [`System`] one recorded frame at a time, with the same timing and viewport size
+/// as when it was recorded. Doesn't need a window.
+#[derive(Debug)]
+pub struct InputReplay {
+	recording: InputRecording,
+	position: usize,
+
+	/// Stands in for the time recording started.
+	start: Instant,
+}
+
+impl InputReplay {
+	pub fn new(recording: InputRecording) -> Self {
+		InputReplay {
+			recording,
+			position: 0,
+			start: Instant::now(),
+		}
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.position >= self.recording.entries.len()
+	}
+
+	/// Sends every event up to the next recorded frame, and then runs that frame.
+	/// Returns false without running anything once the recording is exhausted.
+	pub fn step(&mut self, system: &mut System, painter: &mut Painter, build_ui: impl FnOnce(&Ui<'_>)) -> bool {
+		while let Some(entry) = self.recording.entries.get(self.position) {
+			self.position += 1;
+
+			match entry {
+				RecordedEntry::Event { time, event } => {
+					system.input.send_input_event(event.clone(), self.start + *time);
+				}
+
+				&RecordedEntry::Frame { time, viewport_size: [width, height] } => {
+					system.set_size(Vec2i::new(width as i32, height as i32));
+
+					painter.clear();
+					system.run_at(self.start + time, painter, build_ui);
+					system.prepare_next_frame();
+					return true
+				}
+			}
+		}
+
+		false
+	}
+
+	/// Runs every remaining frame of the recording.
+	pub fn run_to_end(&mut self, system: &mut System, painter: &mut Painter, mut build_ui: impl FnMut(&Ui<'_>)) {
+		while self.step(system, painter, &mut build_ui) {}
+	}
+}
@@ -112,6 +112,10 @@ pub enum WidgetColorRole {
 	Surface,
 	OnSurface,
 
+	InverseSurface,
+	InverseOnSurface,
+	InversePrimary,
+
 	SurfaceContainerHighest,
 	SurfaceContainerHigh,
 	SurfaceContainer,
@@ -120,6 +124,8 @@ pub enum WidgetColorRole {
 
 	Outline,
 	OutlineVariant,
+
+	Scrim,
 }
 
 impl WidgetColorRole {
@@ -139,6 +145,8 @@ impl WidgetColorRole {
 			Error => OnError,
 			ErrorContainer => OnErrorContainer,
 
+			InverseSurface => InverseOnSurface,
+
 			_ => OnSurface,
 		}
 	}
@@ -208,6 +216,10 @@ impl AppStyle {
 			Surface => self.scheme.surface(),
 			OnSurface => self.scheme.on_surface(),
 
+			InverseSurface => self.scheme.inverse_surface(),
+			InverseOnSurface => self.scheme.inverse_on_surface(),
+			InversePrimary => self.scheme.inverse_primary(),
+
 			SurfaceContainerHighest => self.scheme.surface_container_highest(),
 			SurfaceContainerHigh => self.scheme.surface_container_high(),
 			SurfaceContainer => self.scheme.surface_container(),
@@ -216,6 +228,8 @@ impl AppStyle {
 
 			Outline => self.scheme.outline(),
 			OutlineVariant => self.scheme.outline_variant(),
+
+			Scrim => self.scheme.scrim(),
 		};
 
 		Color::from([red, green, blue, alpha]).to_linear()
@@ -1,13 +1,18 @@
 use super::*;
 
+use std::time::{Instant, Duration};
+
 pub struct System {
 	pub viewport: Viewport,
 	pub input: Input,
 	pub text_atlas: RefCell<TextAtlas>,
 	pub min_size: Vec2i,
+	pub clock: FrameClock,
 
 	persistent_state: PersistentState,
+	notifications: RefCell<NotificationQueue>,
 	should_redraw: Cell<bool>,
+	redraw_deadline: Cell<Option<Instant>>,
 
 	widget_constraints: RefCell<LayoutConstraintMap>,
 	widget_layouts: LayoutMap,
@@ -20,9 +25,12 @@ impl System {
 			input: Input::default(),
 			text_atlas: TextAtlas::new().into(),
 			min_size: Vec2i::zero(),
+			clock: FrameClock::new(),
 
 			persistent_state: PersistentState::new(),
+			notifications: Default::default(),
 			should_redraw: Cell::new(true),
+			redraw_deadline: Cell::new(None),
 
 			widget_constraints: Default::default(),
 			widget_layouts: LayoutMap::new(),
@@ -37,38 +45,118 @@ impl System {
 		}
 	}
 
+	/// Set how long the cursor must rest on a widget before its tooltip is shown.
+	pub fn set_tooltip_delay(&mut self, delay: Duration) {
+		self.persistent_state.tooltip.get_mut().delay = delay;
+	}
+
+	/// Set the width of the invisible border around the viewport that resizes the window when dragged.
+	/// Should be zero if the window has decorations or is maximized.
+	pub fn set_window_resize_border(&mut self, width: f32) {
+		self.input.resize_border = width;
+	}
+
 	pub fn prepare_next_frame(&mut self) {
 		self.input.reset();
 		self.input.set_viewport(self.viewport);
 	}
 
+	/// Show or hide the inspector panel, and the debug overlay for the widget under the cursor.
+	pub fn toggle_inspector(&mut self) {
+		self.persistent_state.inspector.get_mut().toggle();
+		self.should_redraw.set(true);
+	}
+
+	pub fn is_inspector_enabled(&self) -> bool {
+		self.persistent_state.inspector.borrow().is_enabled()
+	}
+
 	pub fn should_redraw(&self) -> bool {
 		// TODO(pat.m): only redraw on input events that actually change state
-		self.should_redraw.get() || self.input.events_received_this_frame
+		self.should_redraw.get()
+			|| self.input.events_received_this_frame
+			|| self.redraw_deadline.get().is_some_and(|deadline| deadline <= Instant::now())
+	}
+
+	/// The earliest time something has asked to be redrawn, if anything.
+	pub fn next_redraw_deadline(&self) -> Option<Instant> {
+		self.redraw_deadline.get()
+	}
+
+	/// The layout a widget was given in the last frame, if it was built.
+	pub fn widget_layout(&self, widget_id: WidgetId) -> Option<&Layout> {
+		self.widget_layouts.get(&widget_id)
+	}
+
+	/// Describes the hierarchy built last frame along with each widget's constraints and layout, as readable text.
+	/// Stable between runs, so that it can be checked in and diffed to catch layout regressions.
+	pub fn layout_snapshot(&self) -> String {
+		let mut snapshot = String::new();
+
+		layout_snapshot::write_layout_snapshot(
+			&mut snapshot,
+			&self.persistent_state.hierarchy.borrow(),
+			&self.persistent_state.widgets.borrow(),
+			&self.widget_constraints.borrow(),
+			&self.widget_layouts,
+		);
+
+		snapshot
+	}
+
+	pub fn run(&mut self, painter: &mut Painter, build_ui: impl FnOnce(&Ui<'_>)) {
+		self.run_at(Instant::now(), painter, build_ui);
 	}
 
 	// TODO(pat.m): could this be built around the same mechanism as std::thread::scope?
 	#[instrument(name = "ui::System::run", skip_all)]
-	pub fn run(&mut self, painter: &mut Painter, build_ui: impl FnOnce(&Ui<'_>)) {
+	pub fn run_at(&mut self, now: Instant, painter: &mut Painter, build_ui: impl FnOnce(&Ui<'_>)) {
 		self.should_redraw.set(false);
+		self.redraw_deadline.set(None);
+
+		self.clock.advance(now);
+		self.input.record_frame(now);
 
 		self.persistent_state.hierarchy.get_mut().new_epoch();
 
 		self.input.process_events(self.persistent_state.hierarchy.get_mut());
 
+		let next_notification_update = self.notifications.get_mut()
+			.update(now, &self.input, self.persistent_state.hierarchy.get_mut());
+
+		if let Some(deadline) = next_notification_update {
+			schedule_redraw(&self.redraw_deadline, deadline);
+		}
+
 		self.widget_constraints.get_mut().clear();
+		self.persistent_state.drag_drop.get_mut().new_frame();
 
 		let span = tracing::trace_span!("build_ui").entered();
 
-		build_ui(&Ui {
-			stack: Default::default(),
-			widget_constraints: &self.widget_constraints,
-			should_redraw: &self.should_redraw,
+		{
+			let ui = Ui {
+				stack: Default::default(),
+				widget_constraints: &self.widget_constraints,
+				should_redraw: &self.should_redraw,
+				redraw_deadline: &self.redraw_deadline,
 
-			persistent_state: &self.persistent_state,
-			input: &self.input,
-			text_atlas: &self.text_atlas,
-		});
+				persistent_state: &self.persistent_state,
+				notifications: &self.notifications,
+				input: &self.input,
+				clock: &self.clock,
+				text_atlas: &self.text_atlas,
+			};
+
+			build_ui(&ui);
+
+			ui.update_drag_drop();
+
+			// Build notifications last so they're layered over everything else
+			ui.build_notification_layer();
+
+			// Except for the inspector, which needs to see everything
+			ui.build_inspector_layer();
+		}
 
 		span.exit();
 
@@ -78,12 +166,25 @@ impl System {
 		self.layout_widgets();
 		self.draw_widgets(painter);
 
+		let inspector_changed = self.persistent_state.inspector.get_mut().update(
+			self.persistent_state.hierarchy.get_mut(),
+			self.persistent_state.widgets.get_mut(),
+			self.widget_constraints.get_mut(),
+			&self.widget_layouts,
+			&self.input,
+		);
+
+		if inspector_changed {
+			self.should_redraw.set(true);
+		}
+
 		self.min_size = self.calc_min_size();
 
 		self.input.register_handlers(
 			self.persistent_state.hierarchy.get_mut(),
 			self.persistent_state.widgets.get_mut(),
-			&self.widget_layouts
+			&self.widget_layouts,
+			&self.persistent_state.style,
 		);
 	}
 
@@ -100,8 +201,10 @@ impl System {
 						state: &mut widget_state.state,
 						text_atlas,
 						input: &self.input,
+						clock: &self.clock,
 						widget_id,
 						should_redraw: &self.should_redraw,
+						redraw_deadline: &self.redraw_deadline,
 					});
 				}
 			});
@@ -146,6 +249,7 @@ impl System {
 
 				input: &mut widget_state.config.input,
 				style: &mut widget_state.config.style,
+				cursor: &mut widget_state.config.cursor,
 
 				state: &mut widget_state.state,
 				text_atlas,
@@ -225,8 +329,11 @@ impl System {
 		let text_atlas = self.text_atlas.get_mut();
 		let app_style = &self.persistent_state.style;
 
-		// draw from root to leaves
-		hierarchy.visit_breadth_first(|widget_id, _| {
+		// Reported by whichever text field has focus
+		self.input.ime_cursor_area.set(None);
+
+		// draw from root to leaves, one layer at a time
+		hierarchy.visit_layers_breadth_first(|widget_id, _| {
 			let layout = &self.widget_layouts[&widget_id];
 
 			// Don't draw if not visible
@@ -259,45 +366,35 @@ impl System {
 
 				state: &mut widget_state.state,
 				input: &self.input,
+				clock: &self.clock,
 				widget_id,
+				should_redraw: &self.should_redraw,
+				redraw_deadline: &self.redraw_deadline,
 			});
 		});
 
-		// Debug visualisation
-		// TODO(pat.m): make this a debug setting
 		painter.set_clip_rect(None);
-		painter.set_line_width(1.0);
-
-		if false {
-			// if let Some(hovered_widget) = self.input.hovered_widget {
-			// 	painter.set_color(Color::light_cyan());
-			// 	self.debug_widget(painter, hovered_widget);
-			// }
-
-			if let Some(focus_widget) = self.input.focus_widget {
-				painter.set_color(Color::light_red());
-				self.debug_widget(painter, focus_widget);
-			}
 
-			// if let Some(active_widget) = self.input.active_widget {
-			// 	painter.set_color(Color::light_green());
-			// 	self.debug_widget(painter, active_widget);
-			// }
-
-			// hierarchy.visit_breadth_first(|widget_id, _| {
-			// 	self.debug_widget(painter, widget_id);
-			// });
+		// Only indicate focus if it was moved by keyboard, since clicking already makes it obvious
+		if self.input.focus_visible
+			&& let Some(focus_widget) = self.input.focus_widget
+			&& let Some(layout) = self.widget_layouts.get(&focus_widget)
+		{
+			let rounding = widgets[&focus_widget].config.style.rounding(app_style);
+			let color = app_style.resolve_color_role(WidgetColorRole::Secondary);
+			draw_ring(painter, &layout.box_bounds, color, rounding);
 		}
-	}
-
-	fn debug_widget(&self, painter: &mut Painter, widget_id: WidgetId) {
-		let layout = &self.widget_layouts[&widget_id];
 
-		painter.rect_outline(layout.box_bounds);
+		// Show whether the drop target under the cursor will accept what is being dragged
+		if let Some((target_widget, accepts)) = self.persistent_state.drag_drop.get_mut().hovered_target()
+			&& let Some(layout) = self.widget_layouts.get(&target_widget)
+		{
+			let rounding = widgets[&target_widget].config.style.rounding(app_style);
+			let color_role = if accepts { WidgetColorRole::Primary } else { WidgetColorRole::Error };
+			draw_ring(painter, &layout.box_bounds, app_style.resolve_color_role(color_role), rounding);
+		}
 
-		// if let Some(clip) = self.widget_layouts[&widget_id].clip_rect {
-		// 	painter.rect_outline(clip);
-		// }
+		self.persistent_state.inspector.get_mut().draw_overlay(painter, &self.widget_layouts, &self.input);
 	}
 }
 
@@ -305,6 +402,9 @@ impl System {
 pub struct PersistentState {
 	pub(super) widgets: RefCell<HashMap<WidgetId, WidgetBox>>,
 	pub(super) hierarchy: RefCell<Hierarchy>,
+	pub(super) tooltip: RefCell<TooltipState>,
+	pub(super) drag_drop: RefCell<DragDropState>,
+	pub(super) inspector: RefCell<InspectorState>,
 
 	pub(super) style: AppStyle,
 }
@@ -314,6 +414,9 @@ impl PersistentState {
 		PersistentState {
 			widgets: Default::default(),
 			hierarchy: Default::default(),
+			tooltip: Default::default(),
+			drag_drop: Default::default(),
+			inspector: Default::default(),
 
 			style: AppStyle::new(),
 		}
@@ -353,4 +456,23 @@ fn draw_default_box(painter: &mut Painter, bounds: &Aabb2, app_style: &AppStyle,
 		painter.set_line_width(outline.width);
 		painter.rounded_rect_outline(*bounds, rounding);
 	}
+}
+
+// https://m3.material.io/foundations/interaction/states/state-layers
+/// Draw a ring just outside of `bounds`, e.g., to indicate focus.
+fn draw_ring(painter: &mut Painter, bounds: &Aabb2, color: Color, rounding: painter::BorderRadii) {
+	let thickness = 3.0;
+	let offset = 2.0 + thickness / 2.0;
+
+	let ring_bounds = Aabb2::new(bounds.min - Vec2::splat(offset), bounds.max + Vec2::splat(offset));
+	let ring_rounding = painter::BorderRadii {
+		top_left: rounding.top_left + offset,
+		top_right: rounding.top_right + offset,
+		bottom_left: rounding.bottom_left + offset,
+		bottom_right: rounding.bottom_right + offset,
+	};
+
+	painter.set_color(color);
+	painter.set_line_width(thickness);
+	painter.rounded_rect_outline(ring_bounds, ring_rounding);
 }
\ No newline at end of file
@@ -20,6 +20,10 @@ impl Viewport {
 		Mat2x3::identity()
 	}
 
+	pub fn view_to_physical(&self) -> Mat2x3 {
+		Mat2x3::identity()
+	}
+
 	pub fn view_bounds(&self) -> Aabb2 {
 		Aabb2::new(Vec2::zero(), self.size)
 	}
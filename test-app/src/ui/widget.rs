@@ -1,6 +1,7 @@
 use super::*;
 
 use std::fmt::Debug;
+use std::time::Instant;
 
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
@@ -21,14 +22,21 @@ pub struct LifecycleContext<'a> {
 	pub state: &'a mut StateBox,
 	pub text_atlas: &'a mut super::TextAtlas,
 	pub input: &'a Input,
+	pub clock: &'a FrameClock,
 
 	pub should_redraw: &'a Cell<bool>,
+	pub redraw_deadline: &'a Cell<Option<Instant>>,
 }
 
 impl LifecycleContext<'_> {
 	pub fn trigger_redraw(&self) {
 		self.should_redraw.set(true);
 	}
+
+	/// Request a frame at some point in the future, even if no input arrives before then.
+	pub fn request_redraw_at(&self, deadline: Instant) {
+		schedule_redraw(self.redraw_deadline, deadline);
+	}
 }
 
 
@@ -39,6 +47,7 @@ pub struct ConfigureContext<'a> {
 
 	pub input: &'a mut InputBehaviour,
 	pub style: &'a mut WidgetStyle,
+	pub cursor: &'a mut Option<CursorIcon>,
 
 	pub widget_id: WidgetId,
 	pub state: &'a mut StateBox,
@@ -58,13 +67,33 @@ pub struct DrawContext<'a> {
 	pub state: &'a mut StateBox,
 	pub text_atlas: &'a mut super::TextAtlas,
 	pub input: &'a Input,
+	pub clock: &'a FrameClock,
+
+	pub should_redraw: &'a Cell<bool>,
+	pub redraw_deadline: &'a Cell<Option<Instant>>,
+}
+
+impl DrawContext<'_> {
+	/// Request another frame after this one, e.g., for widgets that animate independently of input.
+	pub fn trigger_redraw(&self) {
+		self.should_redraw.set(true);
+	}
+
+	/// Request a frame at some point in the future, even if no input arrives before then.
+	pub fn request_redraw_at(&self, deadline: Instant) {
+		schedule_redraw(self.redraw_deadline, deadline);
+	}
 }
 
 
-pub trait Widget : AsAny + Debug {
+pub trait Widget : AsAny + HasTypeName + Debug {
 	fn lifecycle(&mut self, _: LifecycleContext<'_>) {}
 	fn configure(&self, _: ConfigureContext<'_>) {}
 	fn draw(&self, _: DrawContext<'_>) {}
+
+	/// Override the area this widget accepts mouse input in.
+	/// By default this is the box bounds, following the rounded corners of the box if one is drawn.
+	fn hit_shape(&self, _: &Layout) -> Option<HitShape> { None }
 }
 
 impl dyn Widget {
@@ -8,6 +8,9 @@ pub struct WidgetConfiguration {
 	// pub layout: ui::LayoutConstraints,
 	pub input: ui::InputBehaviour,
 	pub style: ui::WidgetStyle,
+
+	/// Cursor to show while this widget is hovered or active. Inherited from the nearest ancestor that sets one.
+	pub cursor: Option<ui::CursorIcon>,
 }
 
 
@@ -37,6 +37,11 @@ impl<'ui, T> WidgetRef<'ui, T> {
 		self
 	}
 
+	pub fn with_cursor(self, cursor: CursorIcon) -> Self {
+		self.config().cursor = Some(cursor);
+		self
+	}
+
 	pub fn widget(&self) -> RefMut<'ui, T>
 		where T: Widget
 	{
@@ -82,11 +87,65 @@ impl<'ui, T> WidgetRef<'ui, T> {
 		self.ui.input.hovered_widget == Some(self.widget_id)
 	}
 
+	/// Returns whether this widget was clicked, or activated with the keyboard while focused.
 	pub fn is_clicked(&self) -> bool {
 		self.is_hovered() && self.ui.input.was_mouse_released(ui::MouseButton::Left)
+			|| self.ui.input.was_activated_by_keyboard(self.widget_id)
+	}
+
+	/// Returns how many clicks are in the sequence that clicked this widget, or zero if it wasn't clicked.
+	/// Keyboard activation always counts as a single click.
+	pub fn click_count(&self) -> u32 {
+		if self.is_hovered() && self.ui.input.was_mouse_released(ui::MouseButton::Left) {
+			self.ui.input.click_count(ui::MouseButton::Left)
+		} else if self.ui.input.was_activated_by_keyboard(self.widget_id) {
+			1
+		} else {
+			0
+		}
+	}
+
+	pub fn is_double_clicked(&self) -> bool {
+		self.click_count() == 2
+	}
+
+	pub fn is_triple_clicked(&self) -> bool {
+		self.click_count() == 3
+	}
+
+	/// Returns whether this widget is being pressed and has been held for a while without moving.
+	pub fn is_long_pressed(&self) -> bool {
+		let input = self.ui.input;
+
+		let is_pressed = input.active_widget == Some(self.widget_id) || self.is_hovered();
+		let Some(deadline) = input.long_press_deadline(ui::MouseButton::Left).filter(|_| is_pressed) else {
+			return false
+		};
+
+		if self.ui.clock.now < deadline {
+			self.ui.request_redraw_at(deadline);
+			return false
+		}
+
+		true
+	}
+
+	/// Returns wheel and touchpad gestures received this frame, if this widget is the one they should go to.
+	/// Only widgets with InputBehaviour::SCROLLABLE will receive them.
+	pub fn gestures(&self) -> Option<&'ui GestureInput> {
+		self.ui.input.gestures_for(self.widget_id)
+	}
+
+	pub fn is_focused(&self) -> bool {
+		self.ui.input.focus_widget == Some(self.widget_id)
 	}
 
-	// TODO(pat.m): is focussed
+	/// Move keyboard focus to this widget. Takes effect from the next frame.
+	pub fn request_focus(self) -> Self {
+		self.ui.input.requested_focus.set(Some(self.widget_id));
+		self.ui.trigger_redraw();
+		self
+	}
 }
 
 impl<'ui, T> WidgetRef<'ui, T>
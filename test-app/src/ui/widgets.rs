@@ -3,21 +3,35 @@ use crate::ui::*;
 
 use std::fmt::{self, Debug};
 
+pub mod board;
 pub mod button;
 pub mod checkbox;
+pub mod color_picker;
+pub mod dialog;
+pub mod image;
+pub mod progress;
 pub mod slider;
+pub mod splitter;
 pub mod spring;
 pub mod text;
 pub mod text_edit;
 pub mod toggle;
+pub mod tooltip;
 
+pub use board::*;
 pub use button::*;
 pub use checkbox::*;
+pub use color_picker::*;
+pub use dialog::*;
+pub use image::*;
+pub use progress::*;
 pub use slider::*;
+pub use splitter::*;
 pub use spring::*;
 pub use text::*;
 pub use text_edit::*;
 pub use toggle::*;
+pub use tooltip::*;
 
 
 // https://m3.material.io/components
@@ -0,0 +1,282 @@
+use crate::ui::*;
+use crate::renderer::TextureHandle;
+
+
+const MIN_ZOOM: f32 = 0.1;
+const MAX_ZOOM: f32 = 8.0;
+
+/// How much each line of ctrl+wheel movement zooms by.
+const WHEEL_ZOOM_STEP: f32 = 1.1;
+
+/// How much of the board an item fills after double-clicking it.
+const ZOOM_TO_ITEM_FILL: f32 = 0.9;
+
+const STROKE_WIDTH: f32 = 2.0;
+
+/// Stroke points closer together than this on screen are merged.
+const STROKE_MIN_SPACING: f32 = 2.0;
+
+/// How far the cursor must move from where the board was pressed before a stroke starts.
+const STROKE_START_THRESHOLD: f32 = 4.0;
+
+
+#[derive(Debug, Clone)]
+pub struct BoardItem {
+	pub texture: TextureHandle,
+
+	/// Top left corner of the item, relative to the top left of the board.
+	pub position: Vec2,
+	pub size: Vec2,
+}
+
+
+/// A free-form area that images can be placed anywhere within, and drawn on by dragging.
+/// Can be panned by scrolling and zoomed by pinching or ctrl+scrolling.
+/// Items and strokes are kept in [`BoardState`], so that they don't need to be passed in every frame.
+#[derive(Debug)]
+pub struct Board {}
+
+#[derive(Debug)]
+pub struct BoardState {
+	pub items: Vec<BoardItem>,
+
+	/// Polylines in board space.
+	pub strokes: Vec<Vec<Vec2>>,
+
+	/// Where the board's origin is, relative to the top left of its content bounds.
+	pan: Vec2,
+	zoom: f32,
+
+	/// Top left and size of the content bounds, as of the last draw.
+	origin: Vec2,
+	size: Vec2,
+
+	/// Raw points of the stroke currently being drawn, in board space.
+	stroke: Vec<Vec2>,
+}
+
+impl Default for BoardState {
+	fn default() -> Self {
+		BoardState {
+			items: Vec::new(),
+			strokes: Vec::new(),
+
+			pan: Vec2::zero(),
+			zoom: 1.0,
+			origin: Vec2::zero(),
+			size: Vec2::zero(),
+
+			stroke: Vec::new(),
+		}
+	}
+}
+
+impl Widget for Board {
+	fn lifecycle(&mut self, ctx: LifecycleContext<'_>) {
+		if ctx.event == WidgetLifecycleEvent::Destroyed {
+			return
+		}
+
+		let state = self.get_state_or_default(ctx.state);
+
+		if let Some(gestures) = ctx.input.gestures_for(ctx.widget_id) {
+			state.apply_gestures(gestures, ctx.input.modifiers);
+		}
+
+		let input = ctx.input;
+		let is_hovered = input.hovered_widget == Some(ctx.widget_id);
+		let is_active = input.active_widget == Some(ctx.widget_id);
+
+		// Strokes should follow every movement of the cursor, not just where it is once a frame
+		if is_hovered || is_active {
+			input.request_pointer_history();
+		}
+
+		if is_hovered
+			&& input.was_mouse_released(MouseButton::Left)
+			&& input.click_count(MouseButton::Left) == 2
+			&& let Some(cursor_pos) = input.cursor_pos
+		{
+			state.zoom_to_item_at(cursor_pos);
+		}
+
+		if is_active && let Some(drag_delta) = input.mouse_drag_delta(MouseButton::Left) {
+			let Some(cursor_pos) = input.cursor_pos else {
+				return
+			};
+
+			if state.stroke.is_empty() {
+				// Clicks and small wobbles while pressing shouldn't leave anything behind
+				if drag_delta.x.hypot(drag_delta.y) <= STROKE_START_THRESHOLD {
+					return
+				}
+
+				let press_pos = cursor_pos - drag_delta;
+				state.stroke.push(state.to_board_space(press_pos));
+			}
+
+			// History may not have been recorded yet if the press came in the same frame as the cursor entering
+			match input.pointer_samples() {
+				[] => state.stroke.push(state.to_board_space(cursor_pos)),
+				samples => {
+					let points = samples.iter().map(|sample| state.to_board_space(sample.position)).collect::<Vec<_>>();
+					state.stroke.extend(points);
+				}
+			}
+
+		} else if !state.stroke.is_empty() {
+			let stroke = std::mem::take(&mut state.stroke);
+			let min_spacing = STROKE_MIN_SPACING / state.zoom;
+			let stroke = smooth_polyline(stroke, min_spacing);
+
+			let length: f32 = stroke.windows(2)
+				.map(|pair| {
+					let delta = pair[1] - pair[0];
+					delta.x.hypot(delta.y)
+				})
+				.sum();
+
+			// Strokes that ended up back where they started are too small to see
+			if length >= min_spacing {
+				state.strokes.push(stroke);
+			}
+		}
+	}
+
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.margin.set_default(4.0);
+		ctx.constraints.padding.set_default(0.0);
+
+		ctx.constraints.min_height.set_default(160.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FLEXIBLE);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FLEXIBLE);
+
+		if ctx.style.fill.is_none() && ctx.style.outline.is_none() {
+			ctx.style.set_fill(WidgetColorRole::SurfaceContainerLowest);
+		}
+
+		*ctx.input |= InputBehaviour::SCROLLABLE;
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let bounds = ctx.layout.content_bounds;
+
+		// Items can be placed partially outside of the board
+		let clip_rect = match ctx.layout.clip_rect {
+			Some(parent_clip) => Aabb2::new(
+				Vec2::new(bounds.min.x.max(parent_clip.min.x), bounds.min.y.max(parent_clip.min.y)),
+				Vec2::new(bounds.max.x.min(parent_clip.max.x), bounds.max.y.min(parent_clip.max.y)),
+			),
+
+			None => bounds,
+		};
+
+		let state = self.get_state_or_default(ctx.state);
+		state.origin = bounds.min;
+		state.size = bounds.size();
+
+		ctx.painter.set_clip_rect(clip_rect);
+		ctx.painter.set_color(Color::white());
+
+		for item in state.items.iter() {
+			let min = state.to_view_space(item.position);
+			ctx.painter.image(Aabb2::new(min, min + item.size * state.zoom), item.texture, None);
+		}
+
+		ctx.painter.set_color(ctx.app_style.resolve_color_role(WidgetColorRole::OnSurface));
+		ctx.painter.set_line_width(STROKE_WIDTH * state.zoom);
+
+		let in_progress_stroke = Some(&state.stroke).filter(|stroke| !stroke.is_empty());
+
+		for stroke in state.strokes.iter().chain(in_progress_stroke) {
+			let points = stroke.iter().map(|&point| state.to_view_space(point)).collect::<Vec<_>>();
+			ctx.painter.polyline(&points);
+		}
+
+		ctx.painter.set_clip_rect(ctx.layout.clip_rect);
+	}
+}
+impl StatefulWidget for Board {
+	type State = BoardState;
+}
+
+impl BoardState {
+	pub fn zoom(&self) -> f32 {
+		self.zoom
+	}
+
+	pub fn to_board_space(&self, position: Vec2) -> Vec2 {
+		(position - self.origin - self.pan) / self.zoom
+	}
+
+	pub fn to_view_space(&self, position: Vec2) -> Vec2 {
+		self.origin + self.pan + position * self.zoom
+	}
+
+	/// Fit the top-most item under `position` to the board, centring it.
+	fn zoom_to_item_at(&mut self, position: Vec2) {
+		let board_position = self.to_board_space(position);
+
+		let Some(item) = self.items.iter().rev()
+			.find(|item| Aabb2::new(item.position, item.position + item.size).contains_point(board_position))
+		else {
+			return
+		};
+
+		if item.size.x <= 0.0 || item.size.y <= 0.0 {
+			return
+		}
+
+		let fit_zoom = (self.size.x / item.size.x).min(self.size.y / item.size.y) * ZOOM_TO_ITEM_FILL;
+		let item_center = item.position + item.size / 2.0;
+
+		self.zoom = fit_zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+		self.pan = self.size / 2.0 - item_center * self.zoom;
+	}
+
+	fn apply_gestures(&mut self, gestures: &GestureInput, modifiers: ModifiersState) {
+		let mut zoom_factor = gestures.zoom_factor;
+
+		// Ctrl+wheel zooms rather than pans, as is conventional for canvases
+		if modifiers.control_key() {
+			zoom_factor *= WHEEL_ZOOM_STEP.powf(gestures.wheel_delta.y);
+			self.pan = self.pan + gestures.pan_delta;
+		} else {
+			self.pan = self.pan + gestures.scroll_delta();
+		}
+
+		// Keep whatever is under the cursor in place while zooming
+		let anchor = gestures.centroid.unwrap_or(self.origin + self.pan);
+		let anchor_on_board = self.to_board_space(anchor);
+
+		self.zoom = (self.zoom * zoom_factor).clamp(MIN_ZOOM, MAX_ZOOM);
+		self.pan = anchor - self.origin - anchor_on_board * self.zoom;
+
+		// Rotation gestures are ignored, since items can't be rotated
+	}
+}
+
+
+
+impl Ui<'_> {
+	pub fn board(&self) -> WidgetRef<'_, Board> {
+		self.add_widget(Board{})
+	}
+}
+
+impl WidgetRef<'_, Board> {
+	/// Convert a position in view space to be relative to the board's origin, as of the last frame.
+	/// Returns None if the board hasn't been laid out yet.
+	pub fn to_board_space(&self, position: Vec2) -> Option<Vec2> {
+		if !self.ui.input.registered_widgets.contains_key(&self.widget_id) {
+			return None
+		}
+
+		Some(self.state_or_default().to_board_space(position))
+	}
+
+	pub fn add_items(&self, items: impl IntoIterator<Item=BoardItem>) {
+		self.state_or_default().items.extend(items);
+	}
+}
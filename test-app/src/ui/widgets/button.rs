@@ -4,6 +4,11 @@ use crate::ui::*;
 #[derive(Debug)]
 pub struct Button {}
 
+#[derive(Debug, Default)]
+pub struct ButtonState {
+	state_layer: StateLayer,
+}
+
 impl Widget for Button {
 	fn configure(&self, ctx: ConfigureContext<'_>) {
 		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
@@ -20,7 +25,7 @@ impl Widget for Button {
 		}
 
 		// TODO(pat.m): set_default for input behaviour
-		*ctx.input |= ui::InputBehaviour::OPAQUE;
+		*ctx.input |= ui::InputBehaviour::OPAQUE | ui::InputBehaviour::FOCUSABLE;
 	}
 
 	fn draw(&self, ctx: DrawContext<'_>) {
@@ -29,16 +34,23 @@ impl Widget for Button {
 		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
 		let is_active = ctx.input.active_widget == Some(ctx.widget_id);
 
-		// Paint a state layer to convey widget state
-		if is_hovered || is_active {
-			// TODO(pat.m): should be hot_widget/active_widget?
-			let is_down = ctx.input.is_mouse_down(ui::MouseButton::Left);
+		// TODO(pat.m): should be hot_widget/active_widget?
+		let is_down = (is_hovered || is_active) && ctx.input.is_mouse_down(ui::MouseButton::Left)
+			|| ctx.input.is_held_by_keyboard(ctx.widget_id);
 
+		let now = ctx.clock.now;
+		let state = self.get_state_or_default(ctx.state);
+		let state_layer_opacity = state.state_layer.update(now, is_hovered || is_active, is_down);
+		let is_animating = state.state_layer.is_animating(now);
+
+		if is_animating {
+			ctx.trigger_redraw();
+		}
+
+		// Paint a state layer to convey widget state
+		if state_layer_opacity > 0.0 {
 			// TODO(pat.m): this calculation could be made elsewhere
-			let fill_color = match is_down {
-				true => base_color.with_alpha(0.32),
-				false => base_color.with_alpha(0.16),
-			};
+			let fill_color = base_color.with_alpha(state_layer_opacity);
 
 			let rounding = ctx.style.rounding(ctx.app_style);
 
@@ -48,6 +60,10 @@ impl Widget for Button {
 	}
 }
 
+impl StatefulWidget for Button {
+	type State = ButtonState;
+}
+
 impl Ui<'_> {
 	pub fn button(&self, text: impl Into<String>) -> WidgetRef<'_, Button> {
 		let widget = self.add_widget(Button{});
@@ -8,7 +8,9 @@ impl Widget for Checkbox {
 	fn lifecycle(&mut self, ctx: LifecycleContext<'_>) {
 		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
 		// TODO(pat.m): should use active_widget, not hovered widget
-		if is_hovered && ctx.input.was_mouse_released(MouseButton::Left) {
+		if is_hovered && ctx.input.was_mouse_released(MouseButton::Left)
+			|| ctx.input.was_activated_by_keyboard(ctx.widget_id)
+		{
 			self.value = !self.value;
 			ctx.trigger_redraw();
 		}
@@ -34,7 +36,7 @@ impl Widget for Checkbox {
 		}
 
 		// TODO(pat.m): set_default for input behaviour
-		*ctx.input |= ui::InputBehaviour::OPAQUE;
+		*ctx.input |= ui::InputBehaviour::OPAQUE | ui::InputBehaviour::FOCUSABLE;
 	}
 
 	fn draw(&self, ctx: DrawContext<'_>) {
@@ -42,7 +44,8 @@ impl Widget for Checkbox {
 		let rounding = ctx.style.rounding(ctx.app_style);
 		
 		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
-		let is_active = ctx.input.active_widget == Some(ctx.widget_id);
+		let is_active = ctx.input.active_widget == Some(ctx.widget_id)
+			|| ctx.input.is_held_by_keyboard(ctx.widget_id);
 
 		// Checked state
 		if self.value {
@@ -0,0 +1,727 @@
+use crate::ui::*;
+
+use material_colors::{
+	color::Argb,
+	hct::Hct,
+	palette::TonalPalette,
+};
+
+// https://m3.material.io/styles/color/system/how-the-system-works#8c5bc3b4-d9b5-4f1b-8d03-b0c2c0fc5d1d
+
+
+const MAX_RECENT_COLORS: usize = 8;
+const PALETTE_TONES: [i32; 11] = [0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+
+const MAX_HCT_CHROMA: f32 = 150.0;
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum ColorPickerMode {
+	#[default]
+	Hsv,
+	Hct,
+}
+
+
+/// Hue, saturation, value and alpha, all in the range [0, 1].
+/// Hue, saturation and value are relative to sRGB space.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Hsva {
+	pub h: f32,
+	pub s: f32,
+	pub v: f32,
+	pub a: f32,
+}
+
+/// Hue [0, 360), chroma [0, ~150] and tone [0, 100] as defined by material color utilities.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Hcta {
+	pub h: f32,
+	pub c: f32,
+	pub t: f32,
+	pub a: f32,
+}
+
+
+#[derive(Debug)]
+pub struct ColorPicker {}
+
+#[derive(Debug, Default)]
+pub struct ColorPickerState {
+	pub mode: ColorPickerMode,
+	pub recent_colors: Vec<Color>,
+
+	hsva: Hsva,
+	hcta: Hcta,
+	hex_text: String,
+
+	// The last color either received from or reported to the caller.
+	last_color: Option<Color>,
+	has_uncommitted_changes: bool,
+}
+
+impl Widget for ColorPicker {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Vertical);
+		ctx.constraints.padding.set_default(8.0);
+		ctx.constraints.margin.set_default(4.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		if ctx.style.fill.is_none() && ctx.style.outline.is_none() {
+			ctx.style.set_fill(WidgetColorRole::SurfaceContainerHigh);
+		}
+	}
+}
+
+impl StatefulWidget for ColorPicker {
+	type State = ColorPickerState;
+}
+
+
+
+/// A two dimensional drag area, selecting saturation along the x axis and value along the y axis.
+#[derive(Debug)]
+pub struct SaturationValueArea {
+	pub hue: f32,
+	pub saturation: f32,
+	pub value: f32,
+}
+
+impl Widget for SaturationValueArea {
+	fn lifecycle(&mut self, ctx: LifecycleContext<'_>) {
+		let bounds = self.get_state_or_default(ctx.state).bounds;
+
+		if let Some(t) = drag_position(&ctx, bounds) {
+			self.saturation = t.x;
+			self.value = 1.0 - t.y;
+			ctx.trigger_redraw();
+		}
+	}
+
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.preferred_width.set_default(240.0);
+		ctx.constraints.preferred_height.set_default(160.0);
+		ctx.constraints.margin.set_default(4.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		*ctx.input |= ui::InputBehaviour::OPAQUE;
+
+		if ctx.cursor.is_none() {
+			*ctx.cursor = Some(CursorIcon::Crosshair);
+		}
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let state = self.get_state(ctx.state);
+		let bounds = ctx.layout.box_bounds;
+		state.bounds = bounds;
+
+		let num_cells = 16;
+		let cell_size = bounds.size() / num_cells as f32;
+
+		let color_at = |x: usize, y: usize| {
+			let s = x as f32 / num_cells as f32;
+			let v = 1.0 - y as f32 / num_cells as f32;
+			Hsva{ h: self.hue, s, v, a: 1.0 }.to_color()
+		};
+
+		// Interpolating in linear space isn't quite right for a space defined in sRGB, so split it up a bit
+		for y in 0..num_cells {
+			for x in 0..num_cells {
+				let min = bounds.min + cell_size * Vec2::new(x as f32, y as f32);
+
+				ctx.painter.gradient_rect(Aabb2::new(min, min + cell_size), [
+					color_at(x, y),
+					color_at(x + 1, y),
+					color_at(x + 1, y + 1),
+					color_at(x, y + 1),
+				]);
+			}
+		}
+
+		let handle_pos = bounds.min + bounds.size() * Vec2::new(self.saturation, 1.0 - self.value);
+		draw_handle(ctx.painter, handle_pos, Hsva{ h: self.hue, s: self.saturation, v: self.value, a: 1.0 }.to_color());
+	}
+}
+
+impl StatefulWidget for SaturationValueArea {
+	type State = DragAreaState;
+}
+
+
+
+/// A horizontal strip selecting a single value in [0, 1], displaying a gradient between some set of colors.
+#[derive(Debug)]
+pub struct ColorStrip {
+	pub value: f32,
+	pub gradient: SmallVec<[Color; 16]>,
+	pub checkerboard: bool,
+}
+
+impl Widget for ColorStrip {
+	fn lifecycle(&mut self, ctx: LifecycleContext<'_>) {
+		let bounds = self.get_state_or_default(ctx.state).bounds;
+
+		if let Some(t) = drag_position(&ctx, bounds) {
+			self.value = t.x;
+			ctx.trigger_redraw();
+		}
+	}
+
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.preferred_width.set_default(240.0);
+		ctx.constraints.preferred_height.set_default(16.0);
+		ctx.constraints.margin.set_default(4.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		*ctx.input |= ui::InputBehaviour::OPAQUE;
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let state = self.get_state(ctx.state);
+		let bounds = ctx.layout.box_bounds;
+		state.bounds = bounds;
+
+		if self.checkerboard {
+			draw_checkerboard(ctx.painter, bounds);
+		}
+
+		let num_segments = self.gradient.len().saturating_sub(1);
+		let segment_width = bounds.width() / num_segments.max(1) as f32;
+
+		for (index, colors) in self.gradient.windows(2).enumerate() {
+			let min = bounds.min + Vec2::from_x(segment_width * index as f32);
+			let max = Vec2::new(min.x + segment_width, bounds.max.y);
+
+			ctx.painter.gradient_rect(Aabb2::new(min, max), [colors[0], colors[1], colors[1], colors[0]]);
+		}
+
+		let handle_pos = Vec2::new(bounds.min.x + bounds.width() * self.value, (bounds.min.y + bounds.max.y) / 2.0);
+		let handle_color = sample_gradient(&self.gradient, self.value);
+		draw_handle(ctx.painter, handle_pos, handle_color);
+	}
+}
+
+impl StatefulWidget for ColorStrip {
+	type State = DragAreaState;
+}
+
+
+#[derive(Debug)]
+pub struct DragAreaState {
+	bounds: Aabb2,
+}
+
+impl Default for DragAreaState {
+	fn default() -> Self {
+		DragAreaState { bounds: Aabb2::zero() }
+	}
+}
+
+
+
+/// A clickable block of color.
+#[derive(Debug)]
+pub struct ColorSwatch { pub color: Color }
+
+impl Widget for ColorSwatch {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.preferred_width.set_default(20.0);
+		ctx.constraints.preferred_height.set_default(20.0);
+		ctx.constraints.margin.set_default(2.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		*ctx.input |= ui::InputBehaviour::OPAQUE;
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let bounds = ctx.layout.box_bounds;
+		let rounding = ctx.style.rounding(ctx.app_style);
+
+		if self.color.a < 1.0 {
+			draw_checkerboard(ctx.painter, bounds);
+		}
+
+		ctx.painter.set_color(self.color);
+		ctx.painter.rounded_rect(bounds, rounding);
+
+		ctx.painter.set_line_width(1.0);
+		ctx.painter.set_color(ctx.app_style.resolve_color_role(WidgetColorRole::OutlineVariant));
+		ctx.painter.rounded_rect_outline(bounds, rounding);
+
+		// Paint a state layer to convey widget state
+		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
+		if is_hovered {
+			let base_color = ctx.app_style.resolve_color_role(WidgetColorRole::OnSurface);
+			ctx.painter.set_color(base_color.with_alpha(0.16));
+			ctx.painter.rounded_rect(bounds, rounding);
+		}
+	}
+}
+
+
+
+impl Ui<'_> {
+	/// Displays a color picker for `color`, which is expected to be in linear space.
+	pub fn color_picker(&self, color: &mut Color) -> WidgetRef<'_, ColorPicker> {
+		let picker = self.add_widget(ColorPicker{});
+
+		// Take the state so we aren't holding a borrow while adding children
+		let mut state = std::mem::take(&mut *picker.state_or_default());
+
+		// If the color has changed externally then resync.
+		if state.last_color.map_or(true, |last| !colors_equal(last, *color)) {
+			state.set_color(*color);
+		}
+
+		let mut changed = false;
+		let mut hex_text_edited = false;
+
+		self.with_parent(&picker, || {
+			self.with_horizontal_layout(|| {
+				for (mode, label) in [(ColorPickerMode::Hsv, "HSV"), (ColorPickerMode::Hct, "HCT")] {
+					let button = self.button(label)
+						.with_constraints(|c| c.padding.set_vertical(4.0));
+
+					if state.mode == mode {
+						button.style().set_fill(WidgetColorRole::Primary);
+					}
+
+					if button.is_clicked() {
+						state.mode = mode;
+					}
+				}
+			})
+			.with_constraints(|c| c.padding.set(0.0));
+
+			changed |= match state.mode {
+				ColorPickerMode::Hsv => self.color_picker_hsv_controls(&mut state),
+				ColorPickerMode::Hct => self.color_picker_hct_controls(&mut state),
+			};
+
+			changed |= self.color_picker_alpha_strip(&mut state);
+
+			// Hex entry
+			self.with_horizontal_layout(|| {
+				self.text("#");
+
+				let prev_hex_text = state.hex_text.clone();
+				self.text_edit(&mut state.hex_text)
+					.with_constraints(|c| c.min_width.set(100.0));
+
+				if state.hex_text != prev_hex_text
+					&& let Some(color) = parse_hex_color(&state.hex_text)
+				{
+					let hex_text = std::mem::take(&mut state.hex_text);
+					state.set_color(color);
+					state.hex_text = hex_text;
+					changed = true;
+					hex_text_edited = true;
+				}
+			})
+			.with_constraints(|c| {
+				c.padding.set(0.0);
+				c.content_alignment.set(Align::Middle);
+			});
+
+			// Recently used
+			if !state.recent_colors.is_empty() {
+				self.with_horizontal_layout(|| {
+					for &recent_color in state.recent_colors.clone().iter() {
+						let swatch = self.add_widget(ColorSwatch{ color: recent_color })
+							.drag_source(move || recent_color);
+
+						if swatch.is_clicked() {
+							state.set_color(recent_color);
+							changed = true;
+						}
+					}
+				})
+				.with_constraints(|c| c.padding.set(0.0));
+			}
+		});
+
+		if changed {
+			state.has_uncommitted_changes = true;
+
+			// Don't reformat the hex entry out from under the user while they're typing in it
+			if !hex_text_edited {
+				state.hex_text_from_color();
+			}
+		}
+
+		// Only commit to the recents list once a drag has finished
+		if state.has_uncommitted_changes && !self.input.is_any_mouse_down() {
+			state.has_uncommitted_changes = false;
+			state.push_recent(state.color());
+		}
+
+		*color = state.color();
+		state.last_color = Some(*color);
+
+		*picker.state_or_default() = state;
+
+		picker
+	}
+
+	fn color_picker_hsv_controls(&self, state: &mut ColorPickerState) -> bool {
+		let Hsva{h, s, v, a} = state.hsva;
+
+		let area = self.add_widget(SaturationValueArea{ hue: h, saturation: s, value: v });
+		let (saturation, value) = {
+			let area = area.widget();
+			(area.saturation, area.value)
+		};
+
+		let hue_gradient = (0..=12)
+			.map(|i| Hsva{ h: i as f32 / 12.0, s: 1.0, v: 1.0, a: 1.0 }.to_color())
+			.collect();
+
+		let hue = self.color_strip(h, hue_gradient, false);
+
+		let changed = saturation != s || value != v || hue.is_some();
+
+		if changed {
+			state.set_hsva(Hsva{ h: hue.unwrap_or(h), s: saturation, v: value, a });
+		}
+
+		changed
+	}
+
+	fn color_picker_hct_controls(&self, state: &mut ColorPickerState) -> bool {
+		let Hcta{h, c, t, a} = state.hcta;
+		let num_stops = 12;
+
+		let stops = |f: &dyn Fn(f32) -> Hcta| -> SmallVec<[Color; 16]> {
+			(0..=num_stops)
+				.map(|i| f(i as f32 / num_stops as f32).to_color())
+				.collect()
+		};
+
+		let hue_gradient = stops(&|x| Hcta{ h: x * 360.0, c, t, a: 1.0 });
+		let chroma_gradient = stops(&|x| Hcta{ h, c: x * MAX_HCT_CHROMA, t, a: 1.0 });
+		let tone_gradient = stops(&|x| Hcta{ h, c, t: x * 100.0, a: 1.0 });
+
+		// Only compare normalized values, since scaling them back up doesn't round trip exactly
+		let hue = self.color_strip(h / 360.0, hue_gradient, false).map(|x| x * 360.0);
+		let chroma = self.color_strip(c / MAX_HCT_CHROMA, chroma_gradient, false).map(|x| x * MAX_HCT_CHROMA);
+		let tone = self.color_strip(t / 100.0, tone_gradient, false).map(|x| x * 100.0);
+
+		let mut changed = hue.is_some() || chroma.is_some() || tone.is_some();
+		let mut new_hcta = Hcta{
+			h: hue.unwrap_or(h),
+			c: chroma.unwrap_or(c),
+			t: tone.unwrap_or(t),
+			a,
+		};
+
+		// Tonal palette preview
+		self.with_horizontal_layout(|| {
+			let palette = TonalPalette::from_hct(new_hcta.to_hct());
+
+			for tone in PALETTE_TONES {
+				let color = argb_to_color(palette.tone(tone)).with_alpha(a);
+
+				let swatch = self.add_widget(ColorSwatch{ color })
+					.drag_source(move || color);
+
+				if swatch.is_clicked() {
+					new_hcta.t = tone as f32;
+					changed = true;
+				}
+			}
+		})
+		.with_constraints(|c| c.padding.set(0.0));
+
+		if changed {
+			state.set_hcta(new_hcta);
+		}
+
+		changed
+	}
+
+	fn color_picker_alpha_strip(&self, state: &mut ColorPickerState) -> bool {
+		let opaque = state.color().with_alpha(1.0);
+		let transparent = opaque.with_alpha(0.0);
+		let alpha = state.hsva.a;
+
+		let Some(new_alpha) = self.color_strip(alpha, [transparent, opaque].into_iter().collect(), true) else {
+			return false
+		};
+
+		state.hsva.a = new_alpha;
+		state.hcta.a = new_alpha;
+		true
+	}
+
+	/// Adds a `ColorStrip` for `value`, returning the new value only if it was dragged.
+	fn color_strip(&self, value: f32, gradient: SmallVec<[Color; 16]>, checkerboard: bool) -> Option<f32> {
+		let new_value = self.add_widget(ColorStrip{ value, gradient, checkerboard })
+			.widget().value;
+
+		(new_value != value).then_some(new_value)
+	}
+}
+
+impl WidgetRef<'_, ColorPicker> {
+	pub fn recent_colors(&self) -> Vec<Color> {
+		self.state_or_default().recent_colors.clone()
+	}
+}
+
+
+
+impl ColorPickerState {
+	pub fn color(&self) -> Color {
+		match self.mode {
+			ColorPickerMode::Hsv => self.hsva.to_color(),
+			ColorPickerMode::Hct => self.hcta.to_color(),
+		}
+	}
+
+	fn set_color(&mut self, color: Color) {
+		self.hsva = Hsva::from_color(color, self.hsva);
+		self.hcta = Hcta::from_color(color, self.hcta);
+		self.hex_text_from_color();
+	}
+
+	fn set_hsva(&mut self, hsva: Hsva) {
+		self.hsva = hsva;
+		self.hcta = Hcta::from_color(hsva.to_color(), self.hcta);
+	}
+
+	fn set_hcta(&mut self, hcta: Hcta) {
+		self.hcta = hcta;
+		self.hsva = Hsva::from_color(hcta.to_color(), self.hsva);
+	}
+
+	fn hex_text_from_color(&mut self) {
+		self.hex_text = format_hex_color(self.color());
+	}
+
+	fn push_recent(&mut self, color: Color) {
+		self.recent_colors.retain(|&recent| !colors_equal(recent, color));
+		self.recent_colors.insert(0, color);
+		self.recent_colors.truncate(MAX_RECENT_COLORS);
+	}
+}
+
+
+
+impl Hsva {
+	pub fn to_color(&self) -> Color {
+		let Hsva{h, s, v, a} = *self;
+
+		let h = h.fract() * 6.0;
+		let chroma = v * s;
+		let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+		let m = v - chroma;
+
+		let (r, g, b) = match h as u32 {
+			0 => (chroma, x, 0.0),
+			1 => (x, chroma, 0.0),
+			2 => (0.0, chroma, x),
+			3 => (0.0, x, chroma),
+			4 => (x, 0.0, chroma),
+			_ => (chroma, 0.0, x),
+		};
+
+		Color::from([srgb_to_linear(r + m), srgb_to_linear(g + m), srgb_to_linear(b + m), a])
+	}
+
+	/// Converts from a linear color, taking hue and saturation from `previous` where they would otherwise be undefined.
+	pub fn from_color(color: Color, previous: Hsva) -> Hsva {
+		let r = linear_to_srgb(color.r);
+		let g = linear_to_srgb(color.g);
+		let b = linear_to_srgb(color.b);
+
+		let max = r.max(g).max(b);
+		let min = r.min(g).min(b);
+		let delta = max - min;
+
+		let v = max;
+		let s = if max > 0.0 { delta / max } else { previous.s };
+
+		let h = if delta <= 0.0 {
+			previous.h
+		} else if max == r {
+			((g - b) / delta).rem_euclid(6.0) / 6.0
+		} else if max == g {
+			((b - r) / delta + 2.0) / 6.0
+		} else {
+			((r - g) / delta + 4.0) / 6.0
+		};
+
+		Hsva{ h, s, v, a: color.a }
+	}
+}
+
+impl Hcta {
+	pub fn to_hct(&self) -> Hct {
+		Hct::from(self.h as f64, self.c as f64, self.t as f64)
+	}
+
+	pub fn to_color(&self) -> Color {
+		argb_to_color(Argb::from(self.to_hct())).with_alpha(self.a)
+	}
+
+	/// Converts from a linear color, taking hue and chroma from `previous` where they would otherwise be undefined.
+	pub fn from_color(color: Color, previous: Hcta) -> Hcta {
+		let hct = Hct::new(color_to_argb(color));
+
+		let t = hct.get_tone() as f32;
+		let c = hct.get_chroma() as f32;
+
+		// Greys have no meaningful hue
+		let h = if c > 0.5 { hct.get_hue() as f32 } else { previous.h };
+
+		Hcta{ h, c, t, a: color.a }
+	}
+}
+
+
+
+fn drag_position(ctx: &LifecycleContext<'_>, bounds: Aabb2) -> Option<Vec2> {
+	let is_active = ctx.input.active_widget == Some(ctx.widget_id);
+	if !is_active || bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+		return None
+	}
+
+	let cursor_pos = ctx.input.cursor_pos?;
+	let t = (cursor_pos - bounds.min) / bounds.size();
+
+	Some(Vec2::new(t.x.clamp(0.0, 1.0), t.y.clamp(0.0, 1.0)))
+}
+
+fn draw_handle(painter: &mut Painter, pos: Vec2, color: Color) {
+	painter.set_color(color.with_alpha(1.0));
+	painter.circle(pos, 6.0);
+
+	painter.set_line_width(2.0);
+	painter.set_color(Color::white());
+	painter.circle_outline(pos, 7.0);
+}
+
+fn draw_checkerboard(painter: &mut Painter, bounds: Aabb2) {
+	let cell_size = 6.0;
+
+	painter.set_color([0.8; 3]);
+	painter.rect(bounds);
+
+	painter.set_color([0.5; 3]);
+
+	let num_x = (bounds.width() / cell_size).ceil() as usize;
+	let num_y = (bounds.height() / cell_size).ceil() as usize;
+
+	for y in 0..num_y {
+		for x in (y % 2..num_x).step_by(2) {
+			let min = bounds.min + Vec2::new(x as f32, y as f32) * cell_size;
+			let max = Vec2::new((min.x + cell_size).min(bounds.max.x), (min.y + cell_size).min(bounds.max.y));
+			painter.rect(Aabb2::new(min, max));
+		}
+	}
+}
+
+fn sample_gradient(gradient: &[Color], t: f32) -> Color {
+	let Some(&last) = gradient.last() else {
+		return Color::white()
+	};
+
+	let position = t.clamp(0.0, 1.0) * (gradient.len() - 1) as f32;
+	let index = position.floor() as usize;
+
+	match gradient.get(index + 1) {
+		Some(&next) => position.fract().lerp(gradient[index], next),
+		None => last,
+	}
+}
+
+
+fn color_to_argb(color: Color) -> Argb {
+	let to_u8 = |v: f32| (linear_to_srgb(v.clamp(0.0, 1.0)) * 255.0).round() as u8;
+	let alpha = (color.a.clamp(0.0, 1.0) * 255.0).round() as u8;
+	Argb::new(alpha, to_u8(color.r), to_u8(color.g), to_u8(color.b))
+}
+
+fn argb_to_color(Argb{alpha, red, green, blue}: Argb) -> Color {
+	Color::from([red, green, blue, alpha]).to_linear()
+}
+
+fn colors_equal(lhs: Color, rhs: Color) -> bool {
+	<[f32; 4]>::from(lhs) == <[f32; 4]>::from(rhs)
+}
+
+/// Formats a linear color as an sRGB hex string, omitting alpha if the color is opaque.
+pub fn format_hex_color(color: Color) -> String {
+	let Argb{alpha, red, green, blue} = color_to_argb(color);
+
+	if alpha == 255 {
+		format!("{red:02X}{green:02X}{blue:02X}")
+	} else {
+		format!("{red:02X}{green:02X}{blue:02X}{alpha:02X}")
+	}
+}
+
+/// Parses an sRGB hex string of the form RRGGBB or RRGGBBAA, with an optional leading '#', into a linear color.
+pub fn parse_hex_color(text: &str) -> Option<Color> {
+	let text = text.trim();
+	let text = text.strip_prefix('#').unwrap_or(text);
+
+	if !text.is_ascii() || (text.len() != 6 && text.len() != 8) {
+		return None
+	}
+
+	let channel = |index: usize| u8::from_str_radix(&text[index*2..index*2 + 2], 16).ok();
+
+	let red = channel(0)?;
+	let green = channel(1)?;
+	let blue = channel(2)?;
+	let alpha = match text.len() {
+		8 => channel(3)?,
+		_ => 255,
+	};
+
+	Some(argb_to_color(Argb::new(alpha, red, green, blue)))
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn round_trip(text: &str) -> Option<String> {
+		parse_hex_color(text).map(format_hex_color)
+	}
+
+	#[test]
+	fn hex_colors_round_trip() {
+		assert_eq!(round_trip("3366CC").as_deref(), Some("3366CC"));
+		assert_eq!(round_trip("#3366CC80").as_deref(), Some("3366CC80"));
+		assert_eq!(round_trip(" #00ff7f ").as_deref(), Some("00FF7F"));
+
+		// Opaque alpha is left off
+		assert_eq!(round_trip("123456FF").as_deref(), Some("123456"));
+
+		// Every channel value survives conversion to linear and back
+		for value in 0..255u8 {
+			let text = format!("{value:02X}{value:02X}{value:02X}{value:02X}");
+			assert_eq!(round_trip(&text), Some(text));
+		}
+	}
+
+	#[test]
+	fn invalid_hex_colors_are_rejected() {
+		for text in ["", "#", "12345", "1234567", "123456789", "GG0000", "#12 456", "ÿÿÿ"] {
+			assert!(parse_hex_color(text).is_none(), "'{text}' parsed");
+		}
+	}
+}
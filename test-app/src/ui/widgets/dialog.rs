@@ -0,0 +1,111 @@
+use crate::ui::*;
+
+// https://m3.material.io/components/dialogs/specs
+
+
+/// A root level layer that draws a scrim over everything beneath it, and blocks input to it.
+#[derive(Debug)]
+pub struct Modal {}
+
+impl Widget for Modal {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Vertical);
+		ctx.constraints.content_alignment.set_default(Align::Middle);
+		ctx.constraints.padding.set_default(24.0);
+
+		*ctx.input |= InputBehaviour::MODAL;
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let scrim_color = ctx.app_style.resolve_color_role(WidgetColorRole::Scrim);
+
+		ctx.painter.set_color(scrim_color.with_alpha(0.32));
+		ctx.painter.rect(ctx.layout.box_bounds);
+	}
+}
+
+
+#[derive(Debug)]
+pub struct DialogSurface {}
+
+impl Widget for DialogSurface {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Vertical);
+		ctx.constraints.padding.set_default(24.0);
+
+		ctx.constraints.min_width.set_default(280.0);
+		ctx.constraints.max_width.set_default(560.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		if ctx.style.fill.is_none() {
+			ctx.style.set_fill(WidgetColorRole::SurfaceContainerHigh);
+		}
+
+		if ctx.style.rounding.is_none() {
+			ctx.style.set_rounding(28.0);
+		}
+	}
+}
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DialogResult {
+	/// The button at this index was clicked.
+	Button(usize),
+
+	/// The dialog was dismissed with Escape or by clicking the scrim.
+	Cancelled,
+}
+
+
+impl Ui<'_> {
+	/// Add a modal layer on top of everything else with a dialog surface centred within it.
+	/// `f` builds the contents of the dialog surface.
+	pub fn modal(&self, f: impl FnOnce()) -> WidgetRef<'_, Modal> {
+		let modal = self.add_widget_to(Modal{}, None);
+
+		self.with_parent(&modal, || {
+			self.spring(Axis::Vertical);
+			self.with_parent_widget(DialogSurface{}, f);
+			self.spring(Axis::Vertical);
+		});
+
+		modal
+	}
+
+	/// Show a modal dialog with a title, message and a row of buttons.
+	/// Returns the result on the frame the user picks a button or dismisses the dialog.
+	pub fn message_dialog(&self, title: impl Into<String>, message: impl Into<String>, buttons: &[&str]) -> Option<DialogResult> {
+		let mut result = None;
+
+		let modal = self.modal(|| {
+			self.text(title)
+				.with_constraints(|c| c.margin.bottom.set(16.0));
+
+			self.text(message);
+
+			self.with_horizontal_layout(|| {
+				self.spring(Axis::Horizontal);
+
+				for (index, &label) in buttons.iter().enumerate() {
+					if self.button(label).is_clicked() {
+						result = Some(DialogResult::Button(index));
+					}
+				}
+			})
+			.with_constraints(|c| {
+				c.horizontal_size_policy.set(SizingBehaviour::CAN_GROW);
+				c.padding.set(0.0);
+				c.margin.top.set(24.0);
+			});
+		});
+
+		if result.is_none() && (modal.is_clicked() || self.input.was_key_pressed(NamedKey::Escape)) {
+			result = Some(DialogResult::Cancelled);
+		}
+
+		result
+	}
+}
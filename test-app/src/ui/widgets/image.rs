@@ -0,0 +1,117 @@
+use crate::ui::*;
+use crate::renderer::TextureHandle;
+
+
+/// How an image should be mapped onto its widget bounds if their aspect ratios differ.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum ImageFit {
+	/// Scale the image to fit entirely within the bounds, preserving aspect ratio.
+	#[default]
+	Fit,
+
+	/// Scale the image to cover the bounds entirely, preserving aspect ratio and cropping the excess.
+	Fill,
+
+	/// Scale the image to match the bounds exactly, ignoring aspect ratio.
+	Stretch,
+}
+
+
+#[derive(Debug)]
+pub struct Image {
+	pub texture: TextureHandle,
+	pub fit: ImageFit,
+}
+
+impl Widget for Image {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		let image_size = self.texture.size().to_vec2();
+		let aspect = image_size.x / image_size.y.max(1.0);
+
+		let padding_h = ctx.constraints.padding.horizontal_sum();
+		let padding_v = ctx.constraints.padding.vertical_sum();
+
+		let explicit_width = [&ctx.constraints.preferred_width, &ctx.constraints.min_width].into_iter()
+			.find(|param| param.is_set())
+			.map(|param| param.get() - padding_h);
+
+		let explicit_height = [&ctx.constraints.preferred_height, &ctx.constraints.min_height].into_iter()
+			.find(|param| param.is_set())
+			.map(|param| param.get() - padding_v);
+
+		// Derive whichever dimension hasn't been specified from the image aspect ratio
+		let preferred_size = match (explicit_width, explicit_height) {
+			(Some(width), None) => Vec2::new(width, width / aspect),
+			(None, Some(height)) => Vec2::new(height * aspect, height),
+			(Some(width), Some(height)) => Vec2::new(width, height),
+			(None, None) => image_size,
+		};
+
+		ctx.constraints.preferred_width.set_default(preferred_size.x + padding_h);
+		ctx.constraints.preferred_height.set_default(preferred_size.y + padding_v);
+
+		ctx.constraints.margin.set_default(4.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		*ctx.input |= ui::InputBehaviour::TRANSPARENT;
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let bounds = ctx.layout.content_bounds;
+		let (rect, uv_rect) = image_placement(bounds, self.texture.size().to_vec2(), self.fit);
+
+		ctx.painter.set_color(Color::white());
+		ctx.painter.image(rect, self.texture, uv_rect);
+	}
+}
+
+
+/// Calculate where an image of a given size should be drawn within `bounds`, and which region of the image
+/// should be sampled.
+pub fn image_placement(bounds: Aabb2, image_size: Vec2, fit: ImageFit) -> (Aabb2, Aabb2) {
+	let full_uv_rect = Aabb2::new(Vec2::zero(), Vec2::one());
+
+	let bounds_size = bounds.size();
+	if image_size.x <= 0.0 || image_size.y <= 0.0 || bounds_size.x <= 0.0 || bounds_size.y <= 0.0 {
+		return (bounds, full_uv_rect)
+	}
+
+	let scale = bounds_size / image_size;
+
+	match fit {
+		ImageFit::Stretch => (bounds, full_uv_rect),
+
+		ImageFit::Fit => {
+			let scaled_size = image_size * scale.x.min(scale.y);
+			let min = bounds.min + (bounds_size - scaled_size) / 2.0;
+			(Aabb2::new(min, min + scaled_size), full_uv_rect)
+		}
+
+		ImageFit::Fill => {
+			// Size of the bounds in uv space
+			let uv_size = bounds_size / (image_size * scale.x.max(scale.y));
+			let uv_min = (Vec2::one() - uv_size) / 2.0;
+			(bounds, Aabb2::new(uv_min, uv_min + uv_size))
+		}
+	}
+}
+
+
+
+impl Ui<'_> {
+	pub fn image(&self, texture: TextureHandle) -> WidgetRef<'_, Image> {
+		self.add_widget(Image {
+			texture,
+			fit: ImageFit::default(),
+		})
+	}
+}
+
+impl WidgetRef<'_, Image> {
+	pub fn with_fit(self, fit: ImageFit) -> Self {
+		self.widget().fit = fit;
+		self
+	}
+}
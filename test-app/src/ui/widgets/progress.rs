@@ -0,0 +1,195 @@
+use crate::ui::*;
+
+use std::f32::consts::{TAU, FRAC_PI_2};
+use std::time::Instant;
+
+// https://m3.material.io/components/progress-indicators/specs
+
+
+#[derive(Debug)]
+pub struct LinearProgress { pub value: f32 }
+
+impl Widget for LinearProgress {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.min_width.set_default(40.0);
+		ctx.constraints.preferred_height.set_default(4.0);
+
+		ctx.constraints.margin.set_default(4.0);
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::CAN_GROW);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		*ctx.input |= ui::InputBehaviour::TRANSPARENT;
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let bounds = ctx.layout.content_bounds;
+		let half_height = bounds.height() / 2.0;
+
+		let track_gap = 4.0;
+
+		let indicator_color = ctx.app_style.resolve_color_role(WidgetColorRole::Primary);
+		let track_color = ctx.app_style.resolve_color_role(WidgetColorRole::SecondaryContainer);
+
+		let value = self.value.clamp(0.0, 1.0);
+		let indicator_end = bounds.min.x + bounds.width() * value;
+
+		// Active indicator
+		if value > 0.0 {
+			let indicator_bounds = Aabb2::new(bounds.min, Vec2::new(indicator_end, bounds.max.y));
+
+			ctx.painter.set_color(indicator_color);
+			ctx.painter.rounded_rect(indicator_bounds, half_height);
+		}
+
+		// Track
+		let track_start = if value > 0.0 { indicator_end + track_gap } else { bounds.min.x };
+		if track_start < bounds.max.x {
+			let track_bounds = Aabb2::new(Vec2::new(track_start, bounds.min.y), bounds.max);
+
+			ctx.painter.set_color(track_color);
+			ctx.painter.rounded_rect(track_bounds, half_height);
+		}
+	}
+}
+
+
+
+#[derive(Debug)]
+pub struct CircularProgress { pub value: f32 }
+
+impl Widget for CircularProgress {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		configure_circular_indicator(ctx);
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let value = self.value.clamp(0.0, 1.0);
+
+		// Start from 12 o'clock
+		let start_angle = -FRAC_PI_2;
+		let sweep_angle = value * TAU;
+
+		draw_circular_indicator(ctx, start_angle, sweep_angle, true);
+	}
+}
+
+
+
+#[derive(Debug)]
+pub struct Spinner {}
+
+#[derive(Debug)]
+pub struct SpinnerState {
+	start_time: Instant,
+}
+
+impl Widget for Spinner {
+	fn lifecycle(&mut self, ctx: LifecycleContext<'_>) {
+		let now = ctx.clock.now;
+		self.get_state_or_else(ctx.state, || SpinnerState { start_time: now });
+	}
+
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		configure_circular_indicator(ctx);
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let state = self.get_state(ctx.state);
+		let elapsed = ctx.clock.now.saturating_duration_since(state.start_time).as_secs_f32();
+
+		// The whole indicator rotates at a constant rate, while the arc grows and shrinks.
+		let rotation_period = 1.6;
+		let breathe_period = 1.3;
+
+		let rotation = (elapsed / rotation_period).fract() * TAU;
+		let breathe = ((elapsed / breathe_period).fract() * TAU).sin() * 0.5 + 0.5;
+
+		let min_sweep = 0.1 * TAU;
+		let max_sweep = 0.75 * TAU;
+		let sweep_angle = breathe.lerp(min_sweep, max_sweep);
+
+		// Offset the start as the arc grows so that the leading edge stays roughly in motion
+		let start_angle = rotation - FRAC_PI_2 - sweep_angle / 2.0;
+
+		// Keep frames coming for as long as we're visible
+		ctx.trigger_redraw();
+
+		draw_circular_indicator(ctx, start_angle, sweep_angle, false);
+	}
+}
+
+impl StatefulWidget for Spinner {
+	type State = SpinnerState;
+}
+
+
+
+fn configure_circular_indicator(ctx: ConfigureContext<'_>) {
+	ctx.constraints.preferred_width.set_default(32.0);
+	ctx.constraints.preferred_height.set_default(32.0);
+
+	ctx.constraints.margin.set_default(4.0);
+	ctx.constraints.padding.set_default(2.0);
+
+	ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+	ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+	ctx.constraints.self_alignment.set_default(Align::Middle);
+
+	*ctx.input |= ui::InputBehaviour::TRANSPARENT;
+}
+
+fn draw_circular_indicator(ctx: DrawContext<'_>, start_angle: f32, sweep_angle: f32, draw_track: bool) {
+	let bounds = ctx.layout.content_bounds;
+
+	let line_width = 4.0;
+	let center = (bounds.min + bounds.max) / 2.0;
+	let radius = (bounds.width().min(bounds.height()) - line_width) / 2.0;
+
+	if radius <= 0.0 {
+		return
+	}
+
+	let indicator_color = ctx.app_style.resolve_color_role(WidgetColorRole::Primary);
+	let track_color = ctx.app_style.resolve_color_role(WidgetColorRole::SecondaryContainer);
+
+	let prev_start_cap = ctx.painter.stroke_options.start_cap;
+	let prev_end_cap = ctx.painter.stroke_options.end_cap;
+
+	ctx.painter.set_line_width(line_width);
+	ctx.painter.stroke_options.start_cap = lyon::tessellation::LineCap::Round;
+	ctx.painter.stroke_options.end_cap = lyon::tessellation::LineCap::Round;
+
+	// Leave a gap either side of the indicator, accounting for the round caps
+	let gap_angle = (line_width * 2.0) / radius;
+	let track_sweep = TAU - sweep_angle - gap_angle * 2.0;
+
+	if draw_track && track_sweep > 0.0 {
+		ctx.painter.set_color(track_color);
+		ctx.painter.arc_outline(center, radius, start_angle + sweep_angle + gap_angle, track_sweep);
+	}
+
+	if sweep_angle > 0.0 {
+		ctx.painter.set_color(indicator_color);
+		ctx.painter.arc_outline(center, radius, start_angle, sweep_angle);
+	}
+
+	ctx.painter.stroke_options.start_cap = prev_start_cap;
+	ctx.painter.stroke_options.end_cap = prev_end_cap;
+}
+
+
+
+impl Ui<'_> {
+	pub fn progress_bar(&self, value: f32) -> WidgetRef<'_, LinearProgress> {
+		self.add_widget(LinearProgress{ value })
+	}
+
+	pub fn progress_circle(&self, value: f32) -> WidgetRef<'_, CircularProgress> {
+		self.add_widget(CircularProgress{ value })
+	}
+
+	pub fn spinner(&self) -> WidgetRef<'_, Spinner> {
+		self.add_widget(Spinner{})
+	}
+}
@@ -1,5 +1,8 @@
 use crate::ui::*;
 
+const SMALL_STEP: f32 = 0.01;
+const LARGE_STEP: f32 = 0.1;
+
 #[derive(Debug)]
 pub struct Slider { pub value: f32, }
 
@@ -7,6 +10,10 @@ pub struct Slider { pub value: f32, }
 pub struct SliderState {
 	drag_state: Option<(Vec2, f32)>,
 	handle_travel_length: f32,
+
+	handle_position: SpringAnimation,
+	handle_width: Tween,
+	state_layer: StateLayer,
 }
 
 impl Widget for Slider {
@@ -30,6 +37,26 @@ impl Widget for Slider {
 		} else {
 			state.drag_state = None;
 		}
+
+		if ctx.input.focus_widget == Some(ctx.widget_id) {
+			let prev_value = self.value;
+
+			for key in ctx.input.named_keys_pressed() {
+				self.value = match key {
+					NamedKey::ArrowLeft | NamedKey::ArrowDown => self.value - SMALL_STEP,
+					NamedKey::ArrowRight | NamedKey::ArrowUp => self.value + SMALL_STEP,
+					NamedKey::PageDown => self.value - LARGE_STEP,
+					NamedKey::PageUp => self.value + LARGE_STEP,
+					NamedKey::Home => 0.0,
+					NamedKey::End => 1.0,
+					_ => continue,
+				}.clamp(0.0, 1.0);
+			}
+
+			if self.value != prev_value {
+				ctx.trigger_redraw();
+			}
+		}
 	}
 
 	fn configure(&self, ctx: ConfigureContext<'_>) {
@@ -41,15 +68,39 @@ impl Widget for Slider {
 
 		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::CAN_GROW);
 		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		*ctx.input |= ui::InputBehaviour::FOCUSABLE;
 	}
 
 	fn draw(&self, ctx: DrawContext<'_>) {
+		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
+		let is_active = ctx.input.active_widget == Some(ctx.widget_id);
+
+		let now = ctx.clock.now;
 		let state = self.get_state(ctx.state);
 
 		let bounds = ctx.layout.content_bounds;
 		let half_height = bounds.height() / 2.0;
 
-		let handle_width = 5.0;
+		// Follow the cursor exactly while dragging, but animate any other changes in value
+		if is_active {
+			state.handle_position.snap_to(self.value);
+		} else {
+			state.handle_position.set_target(self.value);
+		}
+
+		let handle_value = state.handle_position.update(ctx.clock.delta);
+
+		// The handle narrows while being dragged
+		state.handle_width.set_target(if is_active { 3.0 } else { 5.0 }, now);
+		let handle_width = state.handle_width.value(now);
+
+		let state_layer_opacity = state.state_layer.update(now, is_hovered, is_active);
+
+		let is_animating = !state.handle_position.is_settled()
+			|| state.handle_width.is_animating(now)
+			|| state.state_layer.is_animating(now);
+
 		let track_width = 6.0;
 		
 		let handle_travel_start = bounds.min.x + half_height;
@@ -57,7 +108,11 @@ impl Widget for Slider {
 
 		state.handle_travel_length = handle_travel_length;
 
-		let handle_pos_x = handle_travel_start + handle_travel_length * self.value;
+		if is_animating {
+			ctx.trigger_redraw();
+		}
+
+		let handle_pos_x = handle_travel_start + handle_travel_length * handle_value;
 
 		let left_center = bounds.min + Vec2::splat(half_height);
 		let right_center = bounds.max - Vec2::splat(half_height);
@@ -67,17 +122,9 @@ impl Widget for Slider {
 		let primary_container_color = ctx.app_style.resolve_color_role(WidgetColorRole::SecondaryContainer);
 
 		let active_track_color = primary_color;
-		let mut inactive_track_color = primary_container_color;
 
 		// Apply state layer
-		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
-		let is_active = ctx.input.active_widget == Some(ctx.widget_id);
-
-		if is_active {
-			inactive_track_color = 0.16f32.lerp(inactive_track_color, primary_color);
-		} else if is_hovered {
-			inactive_track_color = 0.08f32.lerp(inactive_track_color, primary_color);
-		}
+		let inactive_track_color = (state_layer_opacity / 2.0).lerp(primary_container_color, primary_color);
 
 		let handle_color = active_track_color;
 
@@ -0,0 +1,406 @@
+use crate::ui::*;
+
+
+const DIVIDER_THICKNESS: f32 = 8.0;
+
+
+/// A container that lays out panes along `axis`, separated by draggable dividers.
+/// Panes are added through a [`SplitterBuilder`] - see [`Ui::splitter`].
+#[derive(Debug)]
+pub struct Splitter { pub axis: Axis }
+
+#[derive(Debug, Default)]
+pub struct SplitterState {
+	/// Fraction of the available length given to each pane.
+	pub ratios: Vec<f32>,
+
+	/// Length along the main axis available to panes, as of the last draw.
+	total_length: f32,
+
+	/// Length along the main axis taken up by dividers and pane margins, as of the last configure.
+	reserved_length: f32,
+
+	/// Min and max lengths of each pane along the main axis, as of the last configure.
+	pane_limits: Vec<(f32, f32)>,
+
+	divider_ids: Vec<WidgetId>,
+
+	drag: Option<DividerDrag>,
+
+	/// Which divider was used to collapse a pane, and the ratios to restore when uncollapsed.
+	collapsed: Option<(usize, Vec<f32>)>,
+}
+
+#[derive(Debug)]
+struct DividerDrag {
+	index: usize,
+	start_cursor: f32,
+	start_lengths: Vec<f32>,
+	moved: bool,
+}
+
+impl SplitterState {
+	fn pane_limits(&self, index: usize) -> (f32, f32) {
+		let (min, max) = self.pane_limits.get(index).copied()
+			.unwrap_or((0.0, f32::INFINITY));
+
+		(min, max.max(min))
+	}
+
+	/// Resolve pane ratios into lengths along the main axis, respecting pane limits.
+	fn pane_lengths(&self) -> Vec<f32> {
+		let mut lengths: Vec<f32> = self.ratios.iter().enumerate()
+			.map(|(index, ratio)| {
+				let (min, max) = self.pane_limits(index);
+				(ratio * self.total_length).clamp(min, max)
+			})
+			.collect();
+
+		// The last pane takes up whatever is left
+		if let Some((last, rest)) = lengths.split_last_mut() {
+			let (min, max) = self.pane_limits(rest.len());
+			*last = (self.total_length - rest.iter().sum::<f32>()).clamp(min, max);
+		}
+
+		lengths
+	}
+
+	fn set_pane_lengths(&mut self, lengths: &[f32]) {
+		let total_length: f32 = lengths.iter().sum();
+		if total_length <= 0.0 {
+			return
+		}
+
+		self.ratios = lengths.iter()
+			.map(|length| length / total_length)
+			.collect();
+	}
+
+	fn drag_divider(&mut self, index: usize, start_lengths: &[f32], delta: f32) {
+		if index + 1 >= start_lengths.len() {
+			return
+		}
+
+		let (before_min, before_max) = self.pane_limits(index);
+		let (after_min, after_max) = self.pane_limits(index + 1);
+
+		let before = start_lengths[index];
+		let after = start_lengths[index + 1];
+
+		// Only move the divider as far as both panes either side allow
+		let min_delta = (before_min - before).max(after - after_max);
+		let max_delta = (before_max - before).min(after - after_min);
+		if min_delta > max_delta {
+			return
+		}
+
+		let delta = delta.clamp(min_delta, max_delta);
+
+		let mut lengths = start_lengths.to_vec();
+		lengths[index] += delta;
+		lengths[index + 1] -= delta;
+
+		self.set_pane_lengths(&lengths);
+	}
+
+	fn toggle_collapse(&mut self, index: usize) {
+		if index + 1 >= self.ratios.len() {
+			return
+		}
+
+		if let Some((collapsed_index, previous_ratios)) = self.collapsed.take()
+			&& collapsed_index == index
+		{
+			self.ratios = previous_ratios;
+			return
+		}
+
+		let previous_ratios = self.ratios.clone();
+		let mut lengths = self.pane_lengths();
+
+		// Collapse whichever neighbouring pane is smaller, and give its space to the other
+		let (target, other) = match lengths[index] <= lengths[index + 1] {
+			true => (index, index + 1),
+			false => (index + 1, index),
+		};
+
+		let (target_min, _) = self.pane_limits(target);
+		let (_, other_max) = self.pane_limits(other);
+
+		let freed_length = (lengths[target] - target_min)
+			.min(other_max - lengths[other])
+			.max(0.0);
+
+		lengths[target] -= freed_length;
+		lengths[other] += freed_length;
+
+		self.set_pane_lengths(&lengths);
+		self.collapsed = Some((index, previous_ratios));
+	}
+}
+
+impl Widget for Splitter {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(self.axis);
+		ctx.constraints.padding.set_default(0.0);
+
+		let state = self.get_state_or_default(ctx.state);
+		state.pane_limits.clear();
+		state.reserved_length = 0.0;
+
+		for (child_index, child_id) in ctx.children.iter().enumerate() {
+			let child = &ctx.constraint_map[child_id];
+			state.reserved_length += child.margin.axis_sum(self.axis);
+
+			// Panes and dividers are interleaved
+			if child_index % 2 == 0 {
+				let min = child.min_length_unconstrained(self.axis);
+				let max = child.max_length_unconstrained(self.axis);
+				state.pane_limits.push((min, max));
+			} else {
+				state.reserved_length += child.preferred_length(self.axis);
+			}
+		}
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let state = self.get_state(ctx.state);
+
+		let bounds = ctx.layout.content_bounds;
+		let content_length = match self.axis {
+			Axis::Horizontal => bounds.width(),
+			Axis::Vertical => bounds.height(),
+		};
+
+		let total_length = (content_length - state.reserved_length).max(0.0);
+		let length_changed = (total_length - state.total_length).abs() > 0.5;
+		state.total_length = total_length;
+
+		// Pane lengths are resolved while building, so if our length has changed we need another frame to catch up.
+		if length_changed {
+			ctx.trigger_redraw();
+		}
+	}
+}
+
+impl StatefulWidget for Splitter {
+	type State = SplitterState;
+}
+
+
+
+#[derive(Debug)]
+pub struct SplitterPane;
+
+impl Widget for SplitterPane {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Vertical);
+		ctx.constraints.padding.set_default(8.0);
+	}
+}
+
+
+
+#[derive(Debug)]
+pub struct SplitterDivider { pub axis: Axis }
+
+impl Widget for SplitterDivider {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.preferred_length_mut(self.axis).set_default(DIVIDER_THICKNESS);
+		ctx.constraints.size_policy_mut(self.axis).set_default(SizingBehaviour::FIXED);
+		ctx.constraints.size_policy_mut(self.axis.opposite()).set_default(SizingBehaviour::FLEXIBLE);
+
+		if ctx.cursor.is_none() {
+			*ctx.cursor = Some(match self.axis {
+				Axis::Horizontal => CursorIcon::ColResize,
+				Axis::Vertical => CursorIcon::RowResize,
+			});
+		}
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let bounds = ctx.layout.box_bounds;
+		let center = (bounds.min + bounds.max) / 2.0;
+
+		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
+		let is_active = ctx.input.active_widget == Some(ctx.widget_id);
+
+		let (line_bounds, handle_size) = match self.axis {
+			Axis::Horizontal => (
+				Aabb2::new(Vec2::new(center.x - 0.5, bounds.min.y), Vec2::new(center.x + 0.5, bounds.max.y)),
+				Vec2::new(4.0, 24.0),
+			),
+
+			Axis::Vertical => (
+				Aabb2::new(Vec2::new(bounds.min.x, center.y - 0.5), Vec2::new(bounds.max.x, center.y + 0.5)),
+				Vec2::new(24.0, 4.0),
+			),
+		};
+
+		let handle_color = match is_hovered || is_active {
+			true => ctx.app_style.resolve_color_role(WidgetColorRole::Primary),
+			false => ctx.app_style.resolve_color_role(WidgetColorRole::Outline),
+		};
+
+		ctx.painter.set_color(ctx.app_style.resolve_color_role(WidgetColorRole::OutlineVariant));
+		ctx.painter.rect(line_bounds);
+
+		ctx.painter.set_color(handle_color);
+		ctx.painter.rounded_rect(Aabb2::new(center - handle_size / 2.0, center + handle_size / 2.0), 2.0);
+	}
+}
+
+
+
+pub struct SplitterBuilder<'ui> {
+	ui: &'ui Ui<'ui>,
+	axis: Axis,
+
+	pane_lengths: Vec<f32>,
+	pane_count: Cell<usize>,
+	divider_ids: RefCell<Vec<WidgetId>>,
+}
+
+impl<'ui> SplitterBuilder<'ui> {
+	/// Add a new pane to the splitter, inserting a divider if it isn't the first.
+	/// Pane limits are taken from the min and max lengths in the returned widget's constraints.
+	pub fn pane(&self, build: impl FnOnce()) -> WidgetRef<'ui, SplitterPane> {
+		let index = self.pane_count.get();
+		self.pane_count.set(index + 1);
+
+		if index > 0 {
+			let divider = self.ui.add_widget(SplitterDivider { axis: self.axis });
+			self.divider_ids.borrow_mut().push(divider.widget_id);
+		}
+
+		let pane = self.ui.add_widget(SplitterPane);
+
+		{
+			let mut constraints = pane.constraints();
+
+			if let Some(&length) = self.pane_lengths.get(index) {
+				constraints.preferred_length_mut(self.axis).set(length);
+			}
+
+			// The last pane absorbs any difference between the resolved pane lengths and the space actually available,
+			// until the next frame can catch up.
+			let is_last_pane = index + 1 >= self.pane_lengths.len();
+			let main_policy = match is_last_pane {
+				true => SizingBehaviour::FLEXIBLE,
+				false => SizingBehaviour::FIXED,
+			};
+
+			constraints.size_policy_mut(self.axis).set(main_policy);
+			constraints.size_policy_mut(self.axis.opposite()).set(SizingBehaviour::FLEXIBLE);
+		}
+
+		self.ui.with_parent(&pane, build);
+
+		pane
+	}
+}
+
+
+
+impl Ui<'_> {
+	pub fn splitter(&self, axis: Axis, build: impl FnOnce(&SplitterBuilder<'_>)) -> WidgetRef<'_, Splitter> {
+		let splitter = self.add_widget(Splitter { axis });
+
+		// Take state so we're not holding a borrow while adding panes
+		let mut state = std::mem::take(&mut *splitter.state_or_default());
+
+		update_divider_drag(&mut state, self.input, axis);
+
+		let builder = SplitterBuilder {
+			ui: self,
+			axis,
+
+			pane_lengths: state.pane_lengths(),
+			pane_count: Cell::new(0),
+			divider_ids: RefCell::default(),
+		};
+
+		self.with_parent(&splitter, || build(&builder));
+
+		let pane_count = builder.pane_count.get();
+		state.divider_ids = builder.divider_ids.into_inner();
+
+		// Split evenly if panes are added or removed
+		if state.ratios.len() != pane_count {
+			state.ratios = vec![1.0 / pane_count as f32; pane_count];
+			state.drag = None;
+			state.collapsed = None;
+		}
+
+		// Don't let the splitter be squashed smaller than its panes allow.
+		let min_length = state.reserved_length + state.pane_limits.iter().map(|&(min, _)| min).sum::<f32>();
+		splitter.constraints().min_length_mut(axis).set(min_length);
+
+		*splitter.state_or_default() = state;
+
+		splitter
+	}
+
+	pub fn horizontal_splitter(&self, build: impl FnOnce(&SplitterBuilder<'_>)) -> WidgetRef<'_, Splitter> {
+		self.splitter(Axis::Horizontal, build)
+	}
+
+	pub fn vertical_splitter(&self, build: impl FnOnce(&SplitterBuilder<'_>)) -> WidgetRef<'_, Splitter> {
+		self.splitter(Axis::Vertical, build)
+	}
+}
+
+impl WidgetRef<'_, Splitter> {
+	pub fn ratios(&self) -> Vec<f32> {
+		self.state_or_default().ratios.clone()
+	}
+}
+
+
+fn update_divider_drag(state: &mut SplitterState, input: &Input, axis: Axis) {
+	let cursor = input.cursor_pos.map(|pos| match axis {
+		Axis::Horizontal => pos.x,
+		Axis::Vertical => pos.y,
+	});
+
+	let active_divider = state.divider_ids.iter().position(|&id| input.active_widget == Some(id));
+	let hovered_divider = state.divider_ids.iter().position(|&id| input.hovered_widget == Some(id));
+
+	if let Some(index) = active_divider
+		&& let Some(cursor) = cursor
+	{
+		if let Some(drag) = &mut state.drag
+			&& drag.index == index
+		{
+			let delta = cursor - drag.start_cursor;
+			drag.moved |= delta.abs() > 2.0;
+
+			if drag.moved {
+				let start_lengths = drag.start_lengths.clone();
+				state.drag_divider(index, &start_lengths, delta);
+				state.collapsed = None;
+			}
+
+		} else {
+			state.drag = Some(DividerDrag {
+				index,
+				start_cursor: cursor,
+				start_lengths: state.pane_lengths(),
+				moved: false,
+			});
+		}
+
+		return
+	}
+
+	let drag = state.drag.take();
+
+	// Treat a release without much movement as a click, so that double clicking a divider can collapse a pane.
+	if let Some(index) = hovered_divider
+		&& input.was_mouse_released(MouseButton::Left)
+		&& input.click_count(MouseButton::Left) == 2
+		&& !drag.is_some_and(|drag| drag.moved)
+	{
+		state.toggle_collapse(index);
+	}
+}
@@ -18,6 +18,13 @@ pub struct TextEditWidgetState {
 	editor: cosmic_text::Editor<'static>,
 	origin: Vec2,
 	active: bool,
+
+	/// Uncommitted IME text, drawn at the cursor.
+	preedit_buffer: cosmic_text::Buffer,
+	has_preedit: bool,
+
+	/// Where the cursor was last drawn, if it was.
+	cursor_rect: Option<Aabb2>,
 }
 
 impl Widget for TextEdit {
@@ -68,6 +75,12 @@ impl Widget for TextEdit {
 
 		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
 		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		*ctx.input |= ui::InputBehaviour::FOCUSABLE | ui::InputBehaviour::TEXT_INPUT;
+
+		if ctx.cursor.is_none() {
+			*ctx.cursor = Some(CursorIcon::Text);
+		}
 	}
 
 	fn draw(&self, ctx: DrawContext<'_>) {
@@ -90,6 +103,11 @@ impl Widget for TextEdit {
 
 		ctx.painter.set_color(text_color);
 		state.draw(ctx.painter, ctx.text_atlas, start_pos, ctx.app_style);
+
+		// Let the host know where to put the IME candidate window
+		if ctx.input.focus_widget == Some(ctx.widget_id) {
+			ctx.input.ime_cursor_area.set(state.cursor_rect);
+		}
 	}
 }
 
@@ -106,6 +124,9 @@ impl TextEdit {
 
 		state.active = is_focussed || is_active;
 
+		let preedit = ctx.input.ime_preedit.as_ref().filter(|_| is_focussed);
+		state.update_preedit(ctx.text_atlas, preedit);
+
 		if !state.active {
 			return false
 		}
@@ -115,10 +136,17 @@ impl TextEdit {
 
 		if is_hovered {
 			if ctx.input.was_mouse_pressed(ui::MouseButton::Left) {
-				state.editor.action(&mut ctx.text_atlas.font_system, Action::Click {
-					x: relative_mouse.x as i32,
-					y: relative_mouse.y as i32,
-				});
+				let x = relative_mouse.x as i32;
+				let y = relative_mouse.y as i32;
+
+				// Double-click selects a word, triple-click selects a line
+				let action = match ctx.input.click_count(ui::MouseButton::Left) {
+					2 => Action::DoubleClick { x, y },
+					3 => Action::TripleClick { x, y },
+					_ => Action::Click { x, y },
+				};
+
+				state.editor.action(&mut ctx.text_atlas.font_system, action);
 			}
 
 			if let Some(_) = ctx.input.mouse_drag_delta(ui::MouseButton::Left) {
@@ -158,10 +186,19 @@ impl TextEditWidgetState {
 
 		let editor = cosmic_text::Editor::new(buffer);
 
+		let mut preedit_buffer = cosmic_text::Buffer::new(font_system, metrics);
+		preedit_buffer.set_size(font_system, f32::INFINITY, 1000.0);
+		preedit_buffer.set_wrap(font_system, cosmic_text::Wrap::None);
+
 		TextEditWidgetState {
 			editor,
 			origin: Vec2::zero(),
 			active: false,
+
+			preedit_buffer,
+			has_preedit: false,
+
+			cursor_rect: None,
 		}
 	}
 
@@ -178,6 +215,19 @@ impl TextEditWidgetState {
 		});
 	}
 
+	fn update_preedit(&mut self, atlas: &mut TextAtlas, preedit: Option<&ImePreedit>) {
+		let text = preedit.map_or("", |preedit| preedit.text.as_str());
+		let has_preedit = !text.is_empty();
+
+		if !has_preedit && !self.has_preedit {
+			return
+		}
+
+		let attrs = cosmic_text::Attrs::new();
+		self.preedit_buffer.set_text(&mut atlas.font_system, text, attrs, cosmic_text::Shaping::Advanced);
+		self.has_preedit = has_preedit;
+	}
+
 	fn measure(&mut self, atlas: &mut TextAtlas) -> Vec2 {
 		self.editor.with_buffer_mut(|buffer| {
 			let buffer = buffer.borrow_with(&mut atlas.font_system);
@@ -198,12 +248,17 @@ impl TextEditWidgetState {
 		let start_pos = start_pos.into();
 		let text_color = painter.color;
 
+		let mut cursor_rect = None;
+
 		self.editor.with_buffer(|buffer| {
 			// Draw selection highlight and cursor
 			if self.active {
 				for run in buffer.layout_runs() {
 					self.draw_selection_for_run(painter, &run, start_pos, app_style);
-					self.draw_cursor_for_run(painter, &run, start_pos);
+
+					if let Some(rect) = self.draw_cursor_for_run(painter, &run, start_pos) {
+						cursor_rect = Some(rect);
+					}
 				}
 			}
 
@@ -212,7 +267,35 @@ impl TextEditWidgetState {
 			painter.draw_text_buffer(buffer, atlas, start_pos);
 		});
 
+		if self.has_preedit && let Some(cursor_rect) = cursor_rect {
+			self.draw_preedit(painter, atlas, cursor_rect, text_color, app_style);
+		}
+
 		self.origin = start_pos;
+		self.cursor_rect = cursor_rect;
+	}
+
+	/// Draws uncommitted IME text over the top of the text at the cursor, underlined to show that it's still being composed.
+	/// The IME's cursor within the preedit text isn't shown, only the text itself.
+	/// Text after the cursor isn't moved out of the way either, so the background is kept translucent to leave it readable.
+	fn draw_preedit(&self, painter: &mut Painter, atlas: &mut ui::TextAtlas, cursor_rect: Aabb2, text_color: Color, app_style: &AppStyle) {
+		let width = self.preedit_buffer.layout_runs()
+			.map(|run| run.line_w)
+			.max_by(|a, b| a.total_cmp(&b))
+			.unwrap_or(0.0);
+
+		let min = cursor_rect.min;
+		let max = Vec2::new(min.x + width, cursor_rect.max.y);
+
+		painter.set_color(app_style.resolve_color_role(WidgetColorRole::SurfaceContainerHighest).with_alpha(0.6));
+		painter.rect(Aabb2{min, max});
+
+		painter.set_color(text_color);
+		painter.draw_text_buffer(&self.preedit_buffer, atlas, min);
+
+		let underline_y = max.y - 1.0;
+		painter.set_line_width(1.0);
+		painter.line(Vec2::new(min.x, underline_y), Vec2::new(max.x, underline_y));
 	}
 
 	fn draw_selection_for_run(&self, painter: &mut Painter, run: &cosmic_text::LayoutRun<'_>, start_pos: Vec2, app_style: &AppStyle) {
@@ -251,14 +334,15 @@ impl TextEditWidgetState {
 	}
 
 	// Heavily adapted from cosmic_text::Editor::draw
-	fn draw_cursor_for_run(&self, painter: &mut Painter, run: &cosmic_text::LayoutRun<'_>, start_pos: Vec2) {
+	/// Returns the bounds of the cursor if it is on this line.
+	fn draw_cursor_for_run(&self, painter: &mut Painter, run: &cosmic_text::LayoutRun<'_>, start_pos: Vec2) -> Option<Aabb2> {
 		use unicode_segmentation::UnicodeSegmentation;
 
 		let cursor = self.editor.cursor();
 
 		// Cursor isn't on this line
 		if cursor.line != run.line_i {
-			return;
+			return None;
 		}
 
 		// TODO(pat.m): next release of cosmic_text should have an Editor::cursor_position which will mean we can
@@ -304,7 +388,7 @@ impl TextEditWidgetState {
 			}
 
 			// If we can't find what we're looking for, then bail entirely
-			return;
+			return None;
 		};
 
 
@@ -346,6 +430,8 @@ impl TextEditWidgetState {
 		painter.set_color([1.0; 4]);
 		painter.set_line_width(1.0);
 		painter.line(start, end);
+
+		Some(Aabb2{min: start, max: end})
 	}
 }
 
@@ -4,11 +4,20 @@ use crate::ui::*;
 #[derive(Debug)]
 pub struct Toggle { pub value: bool }
 
+#[derive(Debug, Default)]
+pub struct ToggleState {
+	/// 0 when off, 1 when on.
+	handle_position: Tween,
+	state_layer: StateLayer,
+}
+
 impl Widget for Toggle {
 	fn lifecycle(&mut self, ctx: LifecycleContext<'_>) {
 		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
 		// TODO(pat.m): should use active_widget, not hovered widget
-		if is_hovered && ctx.input.was_mouse_released(MouseButton::Left) {
+		if is_hovered && ctx.input.was_mouse_released(MouseButton::Left)
+			|| ctx.input.was_activated_by_keyboard(ctx.widget_id)
+		{
 			self.value = !self.value;
 			ctx.trigger_redraw();
 		}
@@ -37,51 +46,68 @@ impl Widget for Toggle {
 		}
 
 		// TODO(pat.m): set_default for input behaviour
-		*ctx.input |= ui::InputBehaviour::OPAQUE;
+		*ctx.input |= ui::InputBehaviour::OPAQUE | ui::InputBehaviour::FOCUSABLE;
 	}
 
 	fn draw(&self, ctx: DrawContext<'_>) {
 		let rounding = ctx.style.rounding(ctx.app_style);
 		
 		let is_hovered = ctx.input.hovered_widget == Some(ctx.widget_id);
-		let is_active = ctx.input.active_widget == Some(ctx.widget_id);
+		let is_active = ctx.input.active_widget == Some(ctx.widget_id)
+			|| ctx.input.is_held_by_keyboard(ctx.widget_id);
 
 		let bounds = ctx.layout.content_bounds;
 		let base_radius = bounds.height() / 2.0;
 
-		// TODO(pat.m): animate :)
-		let position = match self.value {
-			false => bounds.min + Vec2::splat(base_radius),
-			true => bounds.max - Vec2::splat(base_radius),
-		};
+		let now = ctx.clock.now;
+		let state = self.get_state_or_default(ctx.state);
 
-		let handle_color = match self.value {
-			false => ctx.app_style.resolve_color_role(WidgetColorRole::Outline),
-			true => ctx.app_style.resolve_color_role(WidgetColorRole::OnPrimary),
-		};
+		state.handle_position.set_target(if self.value { 1.0 } else { 0.0 }, now);
+		let handle_t = state.handle_position.value(now);
+		let state_layer_opacity = state.state_layer.update(now, is_hovered, is_active);
+
+		let is_animating = state.handle_position.is_animating(now) || state.state_layer.is_animating(now);
+
+		let off_position = bounds.min + Vec2::splat(base_radius);
+		let on_position = bounds.max - Vec2::splat(base_radius);
+		let position = Vec2::new(handle_t.lerp(off_position.x, on_position.x), off_position.y);
+
+		let off_color = ctx.app_style.resolve_color_role(WidgetColorRole::Outline);
+		let on_color = ctx.app_style.resolve_color_role(WidgetColorRole::OnPrimary);
+		let handle_color = handle_t.lerp(off_color, on_color);
 
-		let handle_radius = match (self.value, is_active) {
-			(_, true) => base_radius * 8.0 / 7.0,
-			(true, _) => base_radius,
-			(false, _) => base_radius * 7.0 / 8.0,
+		let handle_radius = match is_active {
+			true => base_radius * 8.0 / 7.0,
+			false => handle_t.lerp(base_radius * 7.0 / 8.0, base_radius),
 		};
 
 		ctx.painter.set_color(handle_color);
 		ctx.painter.circle(position, handle_radius);
 
+		if is_animating {
+			ctx.trigger_redraw();
+		}
+
 		// Paint a state layer to convey widget state
-		if is_hovered || is_active {
+		if state_layer_opacity > 0.0 {
 			// TODO(pat.m): this calculation could be made elsewhere
 			let base_color = ctx.style.text_color(ctx.app_style);
-			let fill_color = match is_active {
-				false => base_color.with_alpha(0.16),
-				true => base_color.with_alpha(0.32),
-			};
+			let fill_color = base_color.with_alpha(state_layer_opacity);
 
 			ctx.painter.set_color(fill_color);
 			ctx.painter.rounded_rect(ctx.layout.box_bounds, rounding);
 		}
 	}
+
+	fn hit_shape(&self, layout: &Layout) -> Option<HitShape> {
+		// Always a pill, regardless of the style rounding
+		let radius = layout.box_bounds.height() / 2.0;
+		Some(HitShape::RoundedRect(layout.box_bounds, painter::BorderRadii::new(radius)))
+	}
+}
+
+impl StatefulWidget for Toggle {
+	type State = ToggleState;
 }
 
 impl Ui<'_> {
@@ -0,0 +1,229 @@
+use crate::ui::*;
+
+use std::time::{Duration, Instant};
+
+// https://m3.material.io/components/tooltips/specs
+
+
+const DEFAULT_TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+
+
+/// Tracks which widget the cursor is resting on, shared by all tooltips.
+#[derive(Debug)]
+pub struct TooltipState {
+	pub delay: Duration,
+
+	hover_target: Option<(WidgetId, Instant)>,
+
+	/// Set if the mouse is pressed while hovering, so the tooltip doesn't reappear until the cursor leaves.
+	suppressed: bool,
+}
+
+impl Default for TooltipState {
+	fn default() -> Self {
+		TooltipState {
+			delay: DEFAULT_TOOLTIP_DELAY,
+			hover_target: None,
+			suppressed: false,
+		}
+	}
+}
+
+
+#[derive(Debug)]
+pub struct Tooltip {}
+
+#[derive(Debug, Default)]
+pub struct TooltipSurfaceState {
+	/// Size as of the last draw, used for placement.
+	size: Option<Vec2>,
+}
+
+impl Widget for Tooltip {
+	fn configure(&self, ctx: ConfigureContext<'_>) {
+		ctx.constraints.layout_axis.set_default(Axis::Vertical);
+
+		ctx.constraints.min_height.set_default(24.0);
+		ctx.constraints.padding.set_default((8.0, 4.0));
+
+		ctx.constraints.horizontal_size_policy.set_default(SizingBehaviour::FIXED);
+		ctx.constraints.vertical_size_policy.set_default(SizingBehaviour::FIXED);
+
+		// Tooltips shouldn't affect the minimum size of the window.
+		// Deliberately overrides whatever the caller set, rather than being a default.
+		ctx.constraints.min_width.set(0.0);
+
+		if ctx.style.fill.is_none() {
+			ctx.style.set_fill(WidgetColorRole::InverseSurface);
+		}
+
+		*ctx.input |= InputBehaviour::TRANSPARENT;
+	}
+
+	fn draw(&self, ctx: DrawContext<'_>) {
+		let state = self.get_state_or_default(ctx.state);
+		let size = ctx.layout.box_bounds.size();
+
+		let size_changed = match state.size {
+			Some(prev_size) => (prev_size.x - size.x).abs() > 0.5 || (prev_size.y - size.y).abs() > 0.5,
+			None => true,
+		};
+
+		state.size = Some(size);
+
+		// Placement depends on our size, so we need another frame if it changed
+		if size_changed {
+			ctx.trigger_redraw();
+		}
+	}
+}
+
+impl StatefulWidget for Tooltip {
+	type State = TooltipSurfaceState;
+}
+
+
+
+impl<T> WidgetRef<'_, T> {
+	/// Show some text near this widget once the cursor has rested on it for a moment.
+	pub fn tooltip(self, text: impl Into<String>) -> Self {
+		let text = text.into();
+		let ui = self.ui;
+
+		self.tooltip_with(|| { ui.text(text); })
+	}
+
+	/// Like [`WidgetRef::tooltip`], but `build` can add arbitrary widgets to the tooltip.
+	pub fn tooltip_with(self, build: impl FnOnce()) -> Self {
+		if !self.should_show_tooltip() {
+			return self
+		}
+
+		let ui = self.ui;
+
+		let Some(anchor_bounds) = ui.input.registered_widgets.get(&self.widget_id).map(|widget| widget.bounds) else {
+			return self
+		};
+
+		let tooltip = ui.add_widget_to(Tooltip{}, None);
+		ui.with_parent(&tooltip, build);
+
+		let size = tooltip.state_or_default().size.unwrap_or_else(Vec2::zero);
+		let position = place_tooltip(anchor_bounds, size, ui.input.viewport().view_bounds());
+
+		let mut constraints = tooltip.constraints();
+		constraints.margin.left.set(position.x);
+		constraints.margin.top.set(position.y);
+
+		self
+	}
+
+	fn should_show_tooltip(&self) -> bool {
+		let ui = self.ui;
+		let mut state = ui.persistent_state.tooltip.borrow_mut();
+
+		if !self.is_hovered() {
+			if state.hover_target.is_some_and(|(widget_id, _)| widget_id == self.widget_id) {
+				state.hover_target = None;
+				state.suppressed = false;
+			}
+
+			return false
+		}
+
+		let now = ui.clock.now;
+
+		let hover_start = match state.hover_target {
+			Some((widget_id, hover_start)) if widget_id == self.widget_id => hover_start,
+			_ => {
+				state.hover_target = Some((self.widget_id, now));
+				state.suppressed = false;
+				now
+			}
+		};
+
+		if ui.input.is_any_mouse_down() || ui.input.was_mouse_pressed(MouseButton::Left) {
+			state.suppressed = true;
+		}
+
+		if state.suppressed {
+			return false
+		}
+
+		let show_time = hover_start + state.delay;
+		if now < show_time {
+			ui.request_redraw_at(show_time);
+			return false
+		}
+
+		true
+	}
+}
+
+
+/// Centre a tooltip below its anchor, or above if there's no room, keeping it within the viewport.
+fn place_tooltip(anchor: Aabb2, size: Vec2, viewport: Aabb2) -> Vec2 {
+	let gap = 4.0;
+
+	let anchor_center_x = (anchor.min.x + anchor.max.x) / 2.0;
+	let x = anchor_center_x - size.x / 2.0;
+
+	let mut y = anchor.max.y + gap;
+	if y + size.y > viewport.max.y {
+		y = anchor.min.y - gap - size.y;
+	}
+
+	Vec2::new(
+		x.min(viewport.max.x - size.x).max(viewport.min.x),
+		y.min(viewport.max.y - size.y).max(viewport.min.y),
+	)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn viewport() -> Aabb2 {
+		Aabb2::new(Vec2::zero(), Vec2::new(400.0, 300.0))
+	}
+
+	fn assert_inside(position: Vec2, size: Vec2, viewport: Aabb2) {
+		assert!(position.x >= viewport.min.x && position.x + size.x <= viewport.max.x, "x: {}", position.x);
+		assert!(position.y >= viewport.min.y && position.y + size.y <= viewport.max.y, "y: {}", position.y);
+	}
+
+	#[test]
+	fn tooltip_is_centred_below_anchor() {
+		let anchor = Aabb2::new(Vec2::new(100.0, 100.0), Vec2::new(200.0, 120.0));
+		let position = place_tooltip(anchor, Vec2::new(60.0, 20.0), viewport());
+
+		assert_eq!((position.x, position.y), (120.0, 124.0));
+	}
+
+	#[test]
+	fn tooltip_flips_above_anchor_at_bottom_of_viewport() {
+		let anchor = Aabb2::new(Vec2::new(100.0, 270.0), Vec2::new(200.0, 290.0));
+		let position = place_tooltip(anchor, Vec2::new(60.0, 20.0), viewport());
+
+		assert_eq!((position.x, position.y), (120.0, 246.0));
+	}
+
+	#[test]
+	fn tooltip_stays_inside_viewport() {
+		let size = Vec2::new(120.0, 40.0);
+
+		let anchors = [
+			Aabb2::new(Vec2::new(-20.0, -20.0), Vec2::new(10.0, 10.0)),
+			Aabb2::new(Vec2::new(390.0, 0.0), Vec2::new(420.0, 20.0)),
+			Aabb2::new(Vec2::new(0.0, 280.0), Vec2::new(20.0, 310.0)),
+			Aabb2::new(Vec2::new(380.0, 290.0), Vec2::new(400.0, 300.0)),
+			Aabb2::new(Vec2::new(0.0, 0.0), Vec2::new(400.0, 300.0)),
+		];
+
+		for anchor in anchors {
+			assert_inside(place_tooltip(anchor, size, viewport()), size, viewport());
+		}
+	}
+}
@@ -21,10 +21,50 @@ pub trait HasTypeName {
     fn type_name(&self) -> &'static str;
 }
 
-impl<T> HasTypeName for T
-	where T: ?Sized
-{
+// Only implemented for sized types so that trait objects report the name of the concrete type.
+impl<T> HasTypeName for T {
 	fn type_name(&self) -> &'static str {
 		type_name::<T>()
 	}
-}
\ No newline at end of file
+}
+
+
+/// Strips module paths from a type name, including from any generic arguments.
+/// e.g., `test_app::ui::FrameWidget<test_app::ui::BoxLayout>` becomes `FrameWidget<BoxLayout>`.
+pub fn short_type_name(name: &str) -> String {
+	let mut short_name = String::with_capacity(name.len());
+	let mut path_start = 0;
+
+	for (index, ch) in name.char_indices() {
+		if ch.is_alphanumeric() || ch == '_' || ch == ':' {
+			continue
+		}
+
+		let path = &name[path_start..index];
+		short_name.push_str(path.rsplit("::").next().unwrap_or(path));
+		short_name.push(ch);
+		path_start = index + ch.len_utf8();
+	}
+
+	let path = &name[path_start..];
+	short_name.push_str(path.rsplit("::").next().unwrap_or(path));
+	short_name
+}
+
+/// Converts a single sRGB encoded channel in [0, 1] to linear space.
+pub fn srgb_to_linear(v: f32) -> f32 {
+	if v <= 0.04045 {
+		v / 12.92
+	} else {
+		((v + 0.055) / 1.055).powf(2.4)
+	}
+}
+
+/// Converts a single linear channel in [0, 1] to sRGB encoding.
+pub fn linear_to_srgb(v: f32) -> f32 {
+	if v <= 0.0031308 {
+		v * 12.92
+	} else {
+		1.055 * v.powf(1.0 / 2.4) - 0.055
+	}
+}
@@ -1,5 +1,10 @@
 use crate::prelude::*;
 use std::num::Wrapping;
+use std::path::{Path, PathBuf};
+
+
+/// Dropped images are scaled down to fit within this size on the board.
+const MAX_BOARD_ITEM_SIZE: f32 = 240.0;
 
 pub struct View {
 	// item view
@@ -7,24 +12,87 @@ pub struct View {
 	// menus
 
 	pub wants_quit: bool,
+	pub wants_minimize: bool,
+	pub wants_toggle_maximize: bool,
+	pub show_close_dialog: bool,
 	pub frame_counter: Wrapping<u16>,
 
 	pub button_clicks: u32,
 	pub slider_value: f32,
 	pub checkbox_value: bool,
+	pub color_value: Color,
 
 	pub string_value: String,
+
+	pub demo_texture: Option<renderer::TextureHandle>,
+
+	/// Slider value to restore if the 'reset' notification is undone.
+	pub slider_undo: Option<(ui::NotificationId, f32)>,
+
+	/// Images that have been loaded but not yet added to the board.
+	pub new_board_items: Vec<ui::BoardItem>,
+
+	/// Files dropped onto the board that still need loading, and where they were dropped.
+	pub pending_image_drops: Vec<(PathBuf, Vec2)>,
 }
 
 impl View {
 	pub fn new() -> View {
 		View {
 			wants_quit: false,
+			wants_minimize: false,
+			wants_toggle_maximize: false,
+			show_close_dialog: false,
 			frame_counter: Wrapping(0),
 			slider_value: 0.5,
 			button_clicks: 0,
 			checkbox_value: false,
+			color_value: Color::from([0.5, 0.1, 0.2, 1.0]),
 			string_value: String::from("Foobar! I am some text. Hee hee ho ho\na newline? 👀\n\nOh My 🦐\nاَلْعَرَبِيَّةُ"),
+
+			demo_texture: None,
+
+			slider_undo: None,
+
+			new_board_items: Vec::new(),
+			pending_image_drops: Vec::new(),
+		}
+	}
+
+	/// Create or update any gpu resources the view needs before building the ui.
+	pub fn prepare_resources(&mut self, core: &renderer::GraphicsCore, resources: &mut renderer::SharedResources) {
+		if self.demo_texture.is_none() {
+			let size = Vec2i::new(96, 64);
+			let mut data = Vec::with_capacity((size.x * size.y * 4) as usize);
+
+			for y in 0..size.y {
+				for x in 0..size.x {
+					let checker = ((x / 8 + y / 8) % 2) as u8;
+					let red = (x * 255 / size.x) as u8;
+					let green = (y * 255 / size.y) as u8;
+					let blue = 128 + checker * 64;
+					data.extend([red, green, blue, 255]);
+				}
+			}
+
+			self.demo_texture = Some(resources.create_texture(core, size, &data));
+		}
+
+		for (path, position) in self.pending_image_drops.drain(..) {
+			match load_image(&path) {
+				Ok((size, data)) => {
+					let texture = resources.create_texture(core, size, &data);
+					let scale = (MAX_BOARD_ITEM_SIZE / size.x.max(size.y) as f32).min(1.0);
+
+					self.new_board_items.push(ui::BoardItem {
+						texture,
+						position,
+						size: size.to_vec2() * scale,
+					});
+				}
+
+				Err(err) => log::warn!("Failed to load image '{}': {err}", path.display()),
+			}
 		}
 	}
 
@@ -32,11 +100,17 @@ impl View {
 		ui.with_vertical_frame(|| {
 			self.draw_menu_bar(ui);
 
-			ui.with_vertical_layout(|| {
-				self.draw_content(ui);
+			ui.horizontal_splitter(|splitter| {
+				splitter.pane(|| self.draw_content(ui));
+
+				splitter.pane(|| self.draw_inspector(ui))
+					.with_constraints(|c| {
+						c.min_width.set(120.0);
+						c.max_width.set(400.0);
+					});
 			})
 			.with_constraints(|c| {
-				c.set_size_policy(ui::SizingBehaviour::CAN_GROW);
+				c.set_size_policy(ui::SizingBehaviour::FLEXIBLE);
 				c.min_height.set(2.0);
 			});
 
@@ -52,9 +126,45 @@ impl View {
 			c.margin.set(0.0);
 		});
 
+		if self.show_close_dialog {
+			self.draw_close_dialog(ui);
+		}
+
 		self.frame_counter += 1;
 	}
 
+	/// Ask the user to confirm before quitting.
+	pub fn request_close(&mut self) {
+		self.show_close_dialog = true;
+	}
+
+	fn draw_close_dialog(&mut self, ui: &ui::Ui<'_>) {
+		let result = ui.message_dialog(
+			"Unsaved changes",
+			"Save changes to this board before closing?",
+			&["Save", "Discard", "Cancel"]
+		);
+
+		match result {
+			Some(ui::DialogResult::Button(0)) => {
+				// Boards can't be saved to disk yet, so saving just closes for now
+				self.show_close_dialog = false;
+				self.wants_quit = true;
+			}
+
+			Some(ui::DialogResult::Button(1)) => {
+				self.show_close_dialog = false;
+				self.wants_quit = true;
+			}
+
+			Some(_) => {
+				self.show_close_dialog = false;
+			}
+
+			None => {}
+		}
+	}
+
 	fn draw_menu_bar(&mut self, ui: &ui::Ui<'_>) {
 		ui.with_horizontal_frame(|| {
 			ui.button("File").with_constraints(|c| {c.margin.set_horizontal(2.0); c.padding.set_vertical(4.0); });
@@ -63,11 +173,20 @@ impl View {
 
 			ui.spring(ui::Axis::Horizontal);
 
+			if ui.button("_").tooltip("Minimize").is_clicked() {
+				self.wants_minimize = true;
+			}
+
+			if ui.button("□").tooltip("Maximize").is_clicked() {
+				self.wants_toggle_maximize = true;
+			}
+
 			let close_button = ui.button("x")
-				.with_style(|s| s.set_fill(ui::WidgetColorRole::ErrorContainer));
+				.with_style(|s| s.set_fill(ui::WidgetColorRole::ErrorContainer))
+				.tooltip("Close");
 
 			if close_button.is_clicked() {
-				self.wants_quit = true;
+				self.request_close();
 			}
 		})
 		.with_style(|style| {
@@ -118,6 +237,22 @@ impl View {
 		});
 	}
 
+	fn draw_inspector(&mut self, ui: &ui::Ui<'_>) {
+		ui.text("Inspector");
+
+		ui.text(format!("Clicks: {}", self.button_clicks));
+		ui.text(format!("Slider: {:.2}", self.slider_value));
+
+		// Colour swatches can be dropped here
+		let color_target = ui.with_horizontal_frame(|| {
+			ui.text(format!("Color: {}", ui::format_hex_color(self.color_value)));
+		});
+
+		if let Some(color) = color_target.drop_target::<Color>() {
+			self.color_value = color;
+		}
+	}
+
 	fn draw_content(&mut self, ui: &ui::Ui<'_>) {
 		ui.with_horizontal_layout(|| {
 			ui.button("Foo");
@@ -188,6 +323,48 @@ impl View {
 			c.content_alignment.set(ui::Align::Middle);
 		});
 
+		ui.with_horizontal_layout(|| {
+			ui.text("Progress");
+
+			ui.progress_bar(self.slider_value);
+			ui.progress_circle(self.slider_value);
+			ui.spinner();
+		})
+		.with_constraints(|c| {
+			c.horizontal_size_policy.set(ui::SizingBehaviour::CAN_GROW);
+			c.content_alignment.set(ui::Align::Middle);
+		});
+
+		ui.with_horizontal_layout(|| {
+			ui.text("Notifications");
+
+			let save_button = ui.button("Save")
+				.tooltip_with(|| {
+					ui.text("Save the board");
+					ui.text("Shows a notification when done");
+				});
+
+			if save_button.is_clicked() {
+				ui.notify("Board saved");
+			}
+
+			if ui.button("Reset slider").tooltip("Resets the slider to 0.5").is_clicked() {
+				let notification = ui::Notification::new("Slider reset").with_action("Undo");
+				self.slider_undo = Some((ui.notify(notification), self.slider_value));
+				self.slider_value = 0.5;
+			}
+
+			if let Some((notification_id, previous_value)) = self.slider_undo
+				&& ui.was_notification_action_triggered(notification_id)
+			{
+				self.slider_value = previous_value;
+				self.slider_undo = None;
+			}
+		})
+		.with_constraints(|c| {
+			c.content_alignment.set(ui::Align::Middle);
+		});
+
 		ui.with_horizontal_layout(|| {
 			ui.text("Checkbox");
 			ui.checkbox(&mut self.checkbox_value);
@@ -212,13 +389,94 @@ impl View {
 
 		ui.with_horizontal_layout(|| {
 			ui.text("Text Edit");
-			ui.text_edit(&mut self.string_value);
+			let text_edit = ui.text_edit(&mut self.string_value);
+
+			if ui.button("Edit").is_clicked() {
+				text_edit.request_focus();
+			}
 		})
 		.with_constraints(|c| {
 			c.content_alignment.set(ui::Align::Middle);
 		});
+
+		ui.with_horizontal_layout(|| {
+			ui.text("Board");
+
+			let board = ui.board()
+				.tooltip("Drop images or folders of images here. Drag to draw, scroll to pan, pinch or ctrl+scroll to zoom, double-click an image to zoom to it");
+
+			board.add_items(self.new_board_items.drain(..));
+
+			if let Some((paths, drop_position)) = board.file_drop_target()
+				&& let Some(drop_position) = board.to_board_space(drop_position)
+			{
+				let image_paths = paths.iter().flat_map(|path| collect_image_paths(path));
+
+				// Fan out multiple images so they don't all land on top of each other
+				for (index, path) in image_paths.enumerate() {
+					let position = drop_position + Vec2::splat(index as f32 * 24.0);
+					self.pending_image_drops.push((path, position));
+				}
+			}
+		})
+		.with_constraints(|c| {
+			c.horizontal_size_policy.set(ui::SizingBehaviour::FLEXIBLE);
+		});
+
+		if let Some(texture) = self.demo_texture {
+			ui.with_horizontal_layout(|| {
+				ui.text("Image");
+
+				ui.image(texture);
+
+				for fit in [ui::ImageFit::Fit, ui::ImageFit::Fill, ui::ImageFit::Stretch] {
+					ui.image(texture)
+						.with_fit(fit)
+						.with_style(|s| s.set_outline(ui::WidgetColorRole::OutlineVariant))
+						.with_constraints(|c| c.set_size(Vec2::new(48.0, 48.0)));
+				}
+			})
+			.with_constraints(|c| {
+				c.content_alignment.set(ui::Align::Middle);
+			});
+		}
+
+		ui.with_horizontal_layout(|| {
+			ui.text("Color");
+			ui.color_picker(&mut self.color_value);
+
+			ui.add_widget(ui::ColorSwatch{ color: self.color_value })
+				.with_constraints(|c| c.set_size(Vec2::splat(48.0)));
+		});
 	}
 }
 
 
 
+/// Expand directories into the image files directly within them.
+fn collect_image_paths(path: &Path) -> Vec<PathBuf> {
+	if !path.is_dir() {
+		return vec![path.to_owned()]
+	}
+
+	let Ok(entries) = std::fs::read_dir(path) else {
+		return Vec::new()
+	};
+
+	let mut paths: Vec<PathBuf> = entries
+		.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+		.filter(|path| path.extension()
+			.and_then(|ext| ext.to_str())
+			.is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "jpg" | "jpeg" | "png")))
+		.collect();
+
+	paths.sort();
+	paths
+}
+
+fn load_image(path: &Path) -> anyhow::Result<(Vec2i, Vec<u8>)> {
+	let image = ::image::open(path)?.to_rgba8();
+	let (width, height) = image.dimensions();
+
+	Ok((Vec2i::new(width as i32, height as i32), image.into_raw()))
+}